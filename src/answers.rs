@@ -0,0 +1,178 @@
+use std::{collections::BTreeMap, fmt, fs, path::Path, str::FromStr};
+
+use crate::parse::{self, ParseError};
+use crate::runner::{Part, Report};
+
+pub const DEFAULT_PATH: &str = "./answers.toml";
+
+/// Accepted answers, read from a TOML file with one table per day:
+///
+/// ```toml
+/// [day17]
+/// part1 = 1256
+/// part2 = "1382"
+/// ```
+#[derive(Debug, PartialEq, Eq, Default)]
+pub struct Answers {
+    days: BTreeMap<u32, [Option<String>; 2]>,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum Outcome {
+    Pass,
+    Fail { expected: String },
+    Missing,
+}
+
+impl fmt::Display for Outcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Outcome::Pass => write!(f, "PASS"),
+            Outcome::Fail { expected } => write!(f, "FAIL (expected {expected})"),
+            Outcome::Missing => write!(f, "MISSING"),
+        }
+    }
+}
+
+impl Answers {
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let s = fs::read_to_string(path).map_err(|e| format!("cannot read {}: {e}", path.display()))?;
+        s.parse().map_err(|e| format!("{}: {e}", path.display()))
+    }
+
+    pub fn get(&self, day: u32, part: Part) -> Option<&str> {
+        let index = match part {
+            Part::One => 0,
+            Part::Two => 1,
+        };
+        self.days.get(&day).and_then(|answers| answers[index].as_deref())
+    }
+
+    /// Compares every answer in `report` with the recorded one.
+    pub fn verify(&self, report: &Report) -> Vec<(Part, Outcome)> {
+        report
+            .answers
+            .iter()
+            .map(|answer| {
+                let outcome = match self.get(report.day, answer.part) {
+                    None => Outcome::Missing,
+                    Some(expected) if expected == answer.value => Outcome::Pass,
+                    Some(expected) => Outcome::Fail { expected: expected.to_owned() },
+                };
+                (answer.part, outcome)
+            })
+            .collect()
+    }
+}
+
+impl FromStr for Answers {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut answers = Answers::default();
+        let mut day = None;
+        for line in s.lines() {
+            let content = strip_comment(line).trim();
+            if content.is_empty() {
+                continue;
+            }
+            let located = |e: ParseError| e.within(s, line);
+            if let Some(table) = content.strip_prefix("[day").and_then(|t| t.strip_suffix(']')) {
+                day = Some(parse::number::<u32>(line, table).map_err(located)?);
+                continue;
+            }
+            let Some(day) = day else {
+                return Err(located(ParseError::new(line, content, "a `[dayN]` table")));
+            };
+            let (key, value) = parse::split_once(line, content, "=").map_err(located)?;
+            let index = match key.trim() {
+                "part1" => 0,
+                "part2" => 1,
+                _ => return Err(located(ParseError::new(line, key.trim(), "`part1` or `part2`"))),
+            };
+            let value = value.trim();
+            let value = value
+                .strip_prefix('"')
+                .and_then(|v| v.strip_suffix('"'))
+                .unwrap_or(value);
+            answers.days.entry(day).or_default()[index] = Some(value.to_owned());
+        }
+        Ok(answers)
+    }
+}
+
+/// `line` up to its first `#` outside a quoted value.
+fn strip_comment(line: &str) -> &str {
+    let mut quoted = false;
+    for (i, c) in line.char_indices() {
+        match c {
+            '"' => quoted = !quoted,
+            '#' if !quoted => return &line[..i],
+            _ => (),
+        }
+    }
+    line
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+    use crate::runner::Answer;
+
+    const ANSWERS: &str = "\
+# accepted answers
+[day15]
+part1 = 1320
+part2 = \"145\"
+
+[day17]
+part1 = 102 # normal crucible
+";
+
+    #[test]
+    fn answers_from_str() {
+        let answers = Answers::from_str(ANSWERS).unwrap();
+        assert_eq!(Some("1320"), answers.get(15, Part::One));
+        assert_eq!(Some("145"), answers.get(15, Part::Two));
+        assert_eq!(Some("102"), answers.get(17, Part::One));
+        assert_eq!(None, answers.get(17, Part::Two));
+        assert_eq!(None, answers.get(1, Part::One));
+
+        let answers = Answers::from_str("[day8]\npart1 = \"#1 #2\" # quoted").unwrap();
+        assert_eq!(Some("#1 #2"), answers.get(8, Part::One));
+    }
+
+    #[test]
+    fn answers_from_str_errors() {
+        let e = Answers::from_str("part1 = 3").unwrap_err();
+        assert_eq!((1, 1), (e.line, e.column));
+        let e = Answers::from_str("[day1]\npart3 = 3").unwrap_err();
+        assert_eq!((2, 1, "part3"), (e.line, e.column, e.found.as_str()));
+        let e = Answers::from_str("[dayX]").unwrap_err();
+        assert_eq!((1, 5), (e.line, e.column));
+    }
+
+    #[test]
+    fn test_verify() {
+        let answers = Answers::from_str(ANSWERS).unwrap();
+        let answer = |part, value: &str| Answer { part, value: value.to_owned(), elapsed: Duration::ZERO };
+        let report = Report {
+            day: 17,
+            parse: Duration::ZERO,
+            answers: vec![answer(Part::One, "103"), answer(Part::Two, "94")],
+        };
+        let expected = vec![
+            (Part::One, Outcome::Fail { expected: String::from("102") }),
+            (Part::Two, Outcome::Missing),
+        ];
+        assert_eq!(expected, answers.verify(&report));
+        let report = Report {
+            day: 15,
+            answers: vec![answer(Part::One, "1320"), answer(Part::Two, "145")],
+            ..report
+        };
+        assert_eq!(vec![(Part::One, Outcome::Pass), (Part::Two, Outcome::Pass)], answers.verify(&report));
+    }
+}
@@ -0,0 +1,285 @@
+use std::path::PathBuf;
+
+use crate::input::Source;
+use crate::runner::Part;
+
+pub const USAGE: &str = "\
+Usage: aoc-2023-rust run --day <DAY> [--part <1|2>] [--input <PATH>] [INPUT OPTIONS]
+       aoc-2023-rust all [INPUT OPTIONS]
+       aoc-2023-rust verify [--day <DAY>] [--answers <PATH>] [INPUT OPTIONS]
+       aoc-2023-rust dot --day <DAY> [--input <PATH>] [--output <PATH>] [INPUT OPTIONS]
+
+Commands:
+  run    run a single day
+  all    run every implemented day and print a timing table
+  verify check answers of every (or one) day against the answers file
+  dot    write the input of day 8, 19, 20 or 23 as a Graphviz graph
+
+Options:
+  -d, --day <DAY>      puzzle day to run
+  -p, --part <PART>    part to run, both parts are run when omitted
+  -i, --input <PATH>   input file, `-` reads stdin
+  -a, --answers <PATH> answers file, defaults to ./answers.toml
+  -o, --output <PATH>  DOT file to write, defaults to ./day<DAY>.dot
+  -h, --help           print this message
+
+Input options:
+  --input-dir <DIR>    directory holding <DAY>.txt inputs, defaults to
+                       $AOC_INPUT_DIR or ./input
+  --cache-dir <DIR>    read inputs from <DIR>/<YEAR>/<DAY>.txt first, falling
+                       back to the input directory, defaults to $AOC_CACHE_DIR
+  --fetch-dir <DIR>    fill cache misses from <DIR>/<YEAR>/<DAY>.txt, needs a
+                       cache directory";
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum Command {
+    Run(RunArgs),
+    All(AllArgs),
+    Verify(VerifyArgs),
+    Dot(DotArgs),
+    Help,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct RunArgs {
+    pub day: u32,
+    pub part: Option<Part>,
+    pub input: Option<Source>,
+    pub inputs: InputArgs,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct AllArgs {
+    pub inputs: InputArgs,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct VerifyArgs {
+    pub day: Option<u32>,
+    pub answers: Option<PathBuf>,
+    pub inputs: InputArgs,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct DotArgs {
+    pub day: u32,
+    pub input: Option<Source>,
+    pub output: Option<PathBuf>,
+    pub inputs: InputArgs,
+}
+
+/// Options telling where days' default inputs are looked up.
+#[derive(Debug, PartialEq, Eq, Default)]
+pub struct InputArgs {
+    pub input_dir: Option<PathBuf>,
+    pub cache_dir: Option<PathBuf>,
+    pub fetch_dir: Option<PathBuf>,
+}
+
+impl InputArgs {
+    /// Stores `arg` if it is an input option, returning whether it was one.
+    fn parse_option(&mut self, arg: &str, args: &mut impl Iterator<Item = String>) -> Result<bool, String> {
+        let option = match arg {
+            "--input-dir" => &mut self.input_dir,
+            "--cache-dir" => &mut self.cache_dir,
+            "--fetch-dir" => &mut self.fetch_dir,
+            _ => return Ok(false),
+        };
+        *option = Some(PathBuf::from(args.next().ok_or(format!("missing value for `{arg}`"))?));
+        Ok(true)
+    }
+}
+
+pub fn parse_args<I: IntoIterator<Item = String>>(args: I) -> Result<Command, String> {
+    let mut args = args.into_iter();
+    match args.next().as_deref() {
+        None | Some("-h" | "--help" | "help") => Ok(Command::Help),
+        Some("run") => parse_run_args(args).map(Command::Run),
+        Some("all") => parse_all_args(args).map(Command::All),
+        Some("verify") => parse_verify_args(args).map(Command::Verify),
+        Some("dot") => parse_dot_args(args).map(Command::Dot),
+        Some(other) => Err(format!("unknown command `{other}`")),
+    }
+}
+
+fn parse_run_args(mut args: impl Iterator<Item = String>) -> Result<RunArgs, String> {
+    let mut day = None;
+    let mut part = None;
+    let mut input = None;
+    let mut inputs = InputArgs::default();
+    while let Some(arg) = args.next() {
+        if inputs.parse_option(&arg, &mut args)? {
+            continue;
+        }
+        let mut value = |name: &str| args.next().ok_or(format!("missing value for `{name}`"));
+        match arg.as_str() {
+            "-d" | "--day" => day = Some(parse_day(&value(&arg)?)?),
+            "-p" | "--part" => part = Some(parse_part(&value(&arg)?)?),
+            "-i" | "--input" => input = Some(Source::from_arg(&value(&arg)?)),
+            _ => return Err(format!("unexpected argument `{arg}`")),
+        }
+    }
+    let day = day.ok_or("missing required option `--day`")?;
+    Ok(RunArgs { day, part, input, inputs })
+}
+
+fn parse_all_args(mut args: impl Iterator<Item = String>) -> Result<AllArgs, String> {
+    let mut inputs = InputArgs::default();
+    while let Some(arg) = args.next() {
+        if !inputs.parse_option(&arg, &mut args)? {
+            return Err(format!("unexpected argument `{arg}`"));
+        }
+    }
+    Ok(AllArgs { inputs })
+}
+
+fn parse_verify_args(mut args: impl Iterator<Item = String>) -> Result<VerifyArgs, String> {
+    let mut day = None;
+    let mut answers = None;
+    let mut inputs = InputArgs::default();
+    while let Some(arg) = args.next() {
+        if inputs.parse_option(&arg, &mut args)? {
+            continue;
+        }
+        let mut value = |name: &str| args.next().ok_or(format!("missing value for `{name}`"));
+        match arg.as_str() {
+            "-d" | "--day" => day = Some(parse_day(&value(&arg)?)?),
+            "-a" | "--answers" => answers = Some(PathBuf::from(value(&arg)?)),
+            _ => return Err(format!("unexpected argument `{arg}`")),
+        }
+    }
+    Ok(VerifyArgs { day, answers, inputs })
+}
+
+fn parse_dot_args(mut args: impl Iterator<Item = String>) -> Result<DotArgs, String> {
+    let mut day = None;
+    let mut input = None;
+    let mut output = None;
+    let mut inputs = InputArgs::default();
+    while let Some(arg) = args.next() {
+        if inputs.parse_option(&arg, &mut args)? {
+            continue;
+        }
+        let mut value = |name: &str| args.next().ok_or(format!("missing value for `{name}`"));
+        match arg.as_str() {
+            "-d" | "--day" => day = Some(parse_day(&value(&arg)?)?),
+            "-i" | "--input" => input = Some(Source::from_arg(&value(&arg)?)),
+            "-o" | "--output" => output = Some(PathBuf::from(value(&arg)?)),
+            _ => return Err(format!("unexpected argument `{arg}`")),
+        }
+    }
+    let day = day.ok_or("missing required option `--day`")?;
+    Ok(DotArgs { day, input, output, inputs })
+}
+
+fn parse_day(s: &str) -> Result<u32, String> {
+    match s.parse() {
+        Ok(day @ 1..=25) => Ok(day),
+        _ => Err(format!("invalid day `{s}`, expected a number between 1 and 25")),
+    }
+}
+
+fn parse_part(s: &str) -> Result<Part, String> {
+    match s {
+        "1" => Ok(Part::One),
+        "2" => Ok(Part::Two),
+        _ => Err(format!("invalid part `{s}`, expected 1 or 2")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(s: &str) -> Vec<String> {
+        s.split_whitespace().map(String::from).collect()
+    }
+
+    #[test]
+    fn parse_run() {
+        let expected = Command::Run(RunArgs {
+            day: 17,
+            part: Some(Part::Two),
+            input: Some(Source::File(PathBuf::from("in.txt"))),
+            inputs: InputArgs::default(),
+        });
+        assert_eq!(Ok(expected), parse_args(args("run --day 17 --part 2 --input in.txt")));
+    }
+
+    #[test]
+    fn parse_run_defaults() {
+        let expected = Command::Run(RunArgs { day: 3, part: None, input: None, inputs: InputArgs::default() });
+        assert_eq!(Ok(expected), parse_args(args("run -d 3")));
+    }
+
+    #[test]
+    fn parse_run_inputs() {
+        let expected = Command::Run(RunArgs {
+            day: 3,
+            part: None,
+            input: Some(Source::Stdin),
+            inputs: InputArgs {
+                input_dir: Some(PathBuf::from("inputs")),
+                cache_dir: Some(PathBuf::from("cache")),
+                fetch_dir: None,
+            },
+        });
+        assert_eq!(Ok(expected), parse_args(args("run -d 3 -i - --input-dir inputs --cache-dir cache")));
+    }
+
+    #[test]
+    fn parse_all() {
+        assert_eq!(Ok(Command::All(AllArgs { inputs: InputArgs::default() })), parse_args(args("all")));
+        let inputs = InputArgs {
+            input_dir: None,
+            cache_dir: Some(PathBuf::from("cache")),
+            fetch_dir: Some(PathBuf::from("fixtures")),
+        };
+        let expected = Command::All(AllArgs { inputs });
+        assert_eq!(Ok(expected), parse_args(args("all --cache-dir cache --fetch-dir fixtures")));
+        assert!(parse_args(args("all --day 1")).is_err());
+        assert!(parse_args(args("all --input-dir")).is_err());
+    }
+
+    #[test]
+    fn parse_verify() {
+        let expected = Command::Verify(VerifyArgs { day: None, answers: None, inputs: InputArgs::default() });
+        assert_eq!(Ok(expected), parse_args(args("verify")));
+        let expected = Command::Verify(VerifyArgs {
+            day: Some(17),
+            answers: Some(PathBuf::from("a.toml")),
+            inputs: InputArgs::default(),
+        });
+        assert_eq!(Ok(expected), parse_args(args("verify -d 17 --answers a.toml")));
+        assert!(parse_args(args("verify --part 1")).is_err());
+    }
+
+    #[test]
+    fn parse_dot() {
+        let expected = Command::Dot(DotArgs {
+            day: 20,
+            input: Some(Source::Stdin),
+            output: Some(PathBuf::from("modules.dot")),
+            inputs: InputArgs::default(),
+        });
+        assert_eq!(Ok(expected), parse_args(args("dot -d 20 -i - --output modules.dot")));
+        assert!(parse_args(args("dot")).is_err());
+        assert!(parse_args(args("dot -d 8 --part 1")).is_err());
+    }
+
+    #[test]
+    fn parse_help() {
+        assert_eq!(Ok(Command::Help), parse_args(args("")));
+        assert_eq!(Ok(Command::Help), parse_args(args("--help")));
+    }
+
+    #[test]
+    fn parse_errors() {
+        assert!(parse_args(args("walk")).is_err());
+        assert!(parse_args(args("run")).is_err());
+        assert!(parse_args(args("run --day")).is_err());
+        assert!(parse_args(args("run --day 26")).is_err());
+        assert!(parse_args(args("run --day 1 --part 3")).is_err());
+        assert!(parse_args(args("run --day 1 --verbose")).is_err());
+    }
+}
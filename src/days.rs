@@ -1,23 +1,202 @@
-pub mod day2;
-mod day2_fancy;
-pub mod day3;
-pub mod day4;
-pub mod day5;
-pub mod day6;
-pub mod day7;
-pub mod day8;
-pub mod day9;
-pub mod day10;
-pub mod day11;
-pub mod day13;
-pub mod day14;
-pub mod day15;
-pub mod day16;
-pub mod day17;
-pub mod day18;
-pub mod day19;
-pub mod day20;
-pub mod day21;
-pub mod day22;
-pub mod day23;
-pub mod day24;
+use std::{fmt::Display, time::Instant};
+
+use crate::dot::ToDot;
+use crate::parse::ParseError;
+use crate::runner::{Answer, Part, Report};
+
+pub mod day1;
+pub mod day2;
+pub mod day3;
+pub mod day4;
+pub mod day5;
+pub mod day6;
+pub mod day7;
+pub mod day8;
+pub mod day9;
+pub mod day10;
+pub mod day11;
+pub mod day12;
+pub mod day13;
+pub mod day14;
+pub mod day15;
+pub mod day16;
+pub mod day17;
+pub mod day18;
+pub mod day19;
+pub mod day20;
+pub mod day21;
+pub mod day22;
+pub mod day23;
+pub mod day24;
+pub mod day25;
+
+/// A day's puzzle: how to parse its input and how to answer both parts.
+pub trait Solution {
+    const DAY: u32;
+    type Input: Clone;
+    type Answer1: Display;
+    type Answer2: Display;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError>;
+    fn part1(input: Self::Input) -> Self::Answer1;
+    fn part2(input: Self::Input) -> Self::Answer2;
+}
+
+/// Answer type for a part that has no solution yet.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Unsolved;
+
+impl Display for Unsolved {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "unsolved")
+    }
+}
+
+/// Answer type for a part that checks the input fits its assumptions, showing
+/// why it does not instead of a wrong answer.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Checked<T, E>(pub Result<T, E>);
+
+impl<T: Display, E: Display> Display for Checked<T, E> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.0 {
+            Ok(answer) => write!(f, "{answer}"),
+            Err(e) => write!(f, "error: {e}"),
+        }
+    }
+}
+
+/// Parses an input and renders it as a Graphviz graph.
+type DotFn = fn(&str) -> Result<String, ParseError>;
+
+/// Type-erased handle to a `Solution`, so days can be listed and run generically.
+#[derive(Debug, Clone, Copy)]
+pub struct Registered {
+    pub day: u32,
+    solve: fn(&str, &[Part]) -> Result<Report, ParseError>,
+    dot: Option<DotFn>,
+}
+
+impl Registered {
+    const fn new<S: Solution>() -> Self {
+        Registered { day: S::DAY, solve: solve::<S>, dot: None }
+    }
+
+    /// Like `new`, for days whose input can also be exported as a graph.
+    const fn with_dot<S: Solution>() -> Self
+    where
+        S::Input: ToDot,
+    {
+        Registered { day: S::DAY, solve: solve::<S>, dot: Some(dot::<S>) }
+    }
+
+    /// Parses `input` and renders it as a Graphviz graph, if the day supports it.
+    pub fn dot(&self, input: &str) -> Option<Result<String, ParseError>> {
+        self.dot.map(|dot| dot(input))
+    }
+
+    /// Parses `input` once and answers each requested part, timing every step.
+    pub fn solve(&self, input: &str, parts: &[Part]) -> Result<Report, ParseError> {
+        (self.solve)(input, parts)
+    }
+}
+
+fn dot<S: Solution>(input: &str) -> Result<String, ParseError>
+where
+    S::Input: ToDot,
+{
+    let input = S::parse(input).map_err(|e| e.for_day(S::DAY))?;
+    Ok(input.to_dot().to_string())
+}
+
+fn solve<S: Solution>(input: &str, parts: &[Part]) -> Result<Report, ParseError> {
+    let now = Instant::now();
+    let input = S::parse(input).map_err(|e| e.for_day(S::DAY))?;
+    let parse = now.elapsed();
+    let answers = parts
+        .iter()
+        .map(|&part| {
+            let input = input.clone();
+            let now = Instant::now();
+            let value = match part {
+                Part::One => S::part1(input).to_string(),
+                Part::Two => S::part2(input).to_string(),
+            };
+            Answer { part, value, elapsed: now.elapsed() }
+        })
+        .collect();
+    Ok(Report { day: S::DAY, parse, answers })
+}
+
+pub static SOLUTIONS: &[Registered] = &[
+    Registered::new::<day1::Day1>(),
+    Registered::new::<day2::Day2>(),
+    Registered::new::<day3::Day3>(),
+    Registered::new::<day4::Day4>(),
+    Registered::new::<day5::Day5>(),
+    Registered::new::<day6::Day6>(),
+    Registered::new::<day7::Day7>(),
+    Registered::with_dot::<day8::Day8>(),
+    Registered::new::<day9::Day9>(),
+    Registered::new::<day10::Day10>(),
+    Registered::new::<day11::Day11>(),
+    Registered::new::<day12::Day12>(),
+    Registered::new::<day13::Day13>(),
+    Registered::new::<day14::Day14>(),
+    Registered::new::<day15::Day15>(),
+    Registered::new::<day16::Day16>(),
+    Registered::new::<day17::Day17>(),
+    Registered::new::<day18::Day18>(),
+    Registered::with_dot::<day19::Day19>(),
+    Registered::with_dot::<day20::Day20>(),
+    Registered::new::<day21::Day21>(),
+    Registered::new::<day22::Day22>(),
+    Registered::with_dot::<day23::Day23>(),
+    Registered::new::<day24::Day24>(),
+    Registered::new::<day25::Day25>(),
+];
+
+pub fn get(day: u32) -> Option<&'static Registered> {
+    SOLUTIONS.iter().find(|s| s.day == day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn registry_is_sorted_and_unique() {
+        assert!(SOLUTIONS.windows(2).all(|w| w[0].day < w[1].day));
+    }
+
+    #[test]
+    fn solve_reports_day_of_parse_error() {
+        let e = get(24).unwrap().solve("19, 13, 30 @ -2, 1, -2\n18, 19, z @ -1, -1, -2", &Part::both()).unwrap_err();
+        assert_eq!((Some(24), 2, 9), (e.day, e.line, e.column));
+    }
+
+    #[test]
+    fn test_get() {
+        assert_eq!(Some(15), get(15).map(|s| s.day));
+        assert_eq!(Some(6), get(6).map(|s| s.day));
+        assert_eq!(None, get(26).map(|s| s.day));
+    }
+
+    #[test]
+    fn test_dot() {
+        assert!(get(15).unwrap().dot("").is_none());
+        let dot = get(8).unwrap().dot("LR\n\nAAA = (BBB, BBB)\nBBB = (AAA, AAA)").unwrap().unwrap();
+        assert!(dot.starts_with("digraph \"day 8\" {"));
+        let e = get(20).unwrap().dot("broadcaster => a").unwrap().unwrap_err();
+        assert_eq!(Some(20), e.day);
+    }
+
+    #[test]
+    fn test_solve() {
+        let input = "rn=1,cm-,qp=3,cm=2,qp-,pc=4,ot=9,ab=5,pc-,pc=6,ot=7";
+        let report = get(15).unwrap().solve(input, &Part::both()).unwrap();
+        assert_eq!(15, report.day);
+        assert_eq!(Some("1320"), report.answer(Part::One));
+        assert_eq!(Some("145"), report.answer(Part::Two));
+    }
+}
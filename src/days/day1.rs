@@ -0,0 +1,118 @@
+use std::str::FromStr;
+
+use crate::days::Solution;
+use crate::parse::{self, ParseError};
+
+const DIGIT_WORDS: [&str; 9] = ["one", "two", "three", "four", "five", "six", "seven", "eight", "nine"];
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Input {
+    lines: Vec<String>,
+}
+
+impl FromStr for Input {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let lines = parse::lines(s, |line| {
+            match line.char_indices().find(|(_, c)| !c.is_ascii_alphanumeric()) {
+                Some((i, c)) => Err(ParseError::new(line, &line[i..i + c.len_utf8()], "a letter or digit")),
+                None => Ok(line.to_owned()),
+            }
+        })?;
+        Ok(Input { lines })
+    }
+}
+
+pub struct Day1;
+
+impl Solution for Day1 {
+    const DAY: u32 = 1;
+    type Input = Input;
+    type Answer1 = u32;
+    type Answer2 = u32;
+
+    fn parse(input: &str) -> Result<Input, ParseError> {
+        input.parse()
+    }
+
+    fn part1(input: Input) -> u32 {
+        part1(input)
+    }
+
+    fn part2(input: Input) -> u32 {
+        part2(input)
+    }
+}
+
+pub fn part1(input: Input) -> u32 {
+    input.lines
+        .iter()
+        .map(|line| calibration_value(line.chars().filter_map(|c| c.to_digit(10))))
+        .sum()
+}
+
+pub fn part2(input: Input) -> u32 {
+    input.lines
+        .iter()
+        .map(|line| calibration_value((0..line.len()).filter_map(|i| digit_at(line, i))))
+        .sum()
+}
+
+/// First and last digit combined into a two-digit number, 0 without digits.
+fn calibration_value(mut digits: impl Iterator<Item = u32>) -> u32 {
+    let Some(first) = digits.next() else {
+        return 0;
+    };
+    let last = digits.last().unwrap_or(first);
+    first * 10 + last
+}
+
+/// Digit written at byte `i` of `line`, either as a digit or spelled out.
+/// Words may overlap, so "eightwo" has an 8 at 0 and a 2 at 4.
+fn digit_at(line: &str, i: usize) -> Option<u32> {
+    let rest = &line[i..];
+    rest.chars().next()?.to_digit(10).or_else(|| {
+        DIGIT_WORDS
+            .iter()
+            .position(|word| rest.starts_with(word))
+            .map(|d| d as u32 + 1)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INPUT: &str = "\
+1abc2
+pqr3stu8vwx
+a1b2c3d4e5f
+treb7uchet";
+
+    const INPUT2: &str = "\
+two1nine
+eightwothree
+abcone2threexyz
+xtwone3four
+4nineeightseven2
+zoneight234
+7pqrstsixteen";
+
+    #[test]
+    fn test_part1() {
+        assert_eq!(142, part1(Input::from_str(INPUT).unwrap()));
+    }
+
+    #[test]
+    fn test_part2() {
+        assert_eq!(281, part2(Input::from_str(INPUT2).unwrap()));
+        assert_eq!(82, part2(Input::from_str("eightwo").unwrap()));
+    }
+
+    #[test]
+    fn input_from_str_error() {
+        let e = Input::from_str("1abc2\npq r").unwrap_err();
+        assert_eq!((2, 3), (e.line, e.column));
+    }
+}
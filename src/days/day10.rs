@@ -1,34 +1,32 @@
-use std::{collections::HashSet, str::FromStr, string::ParseError};
+use std::{collections::HashSet, str::FromStr};
 
-use crate::geometry::manhattan_distance_usize;
+use crate::geometry::{Grid, DOWN, LEFT, RIGHT, UP};
+use crate::days::Solution;
+use crate::parse::ParseError;
 
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, PartialEq, Eq, Clone)]
 pub struct Input {
-    map: Vec<Vec<u8>>,
-    start: (usize, usize)
+    map: Grid<u8>,
+    start: (i32, i32)
 }
 
 impl Input {
-    fn move_next(&self, from: (usize, usize), to: (usize, usize)) -> Option<(usize, usize)> {
-        if manhattan_distance_usize(from, to) != 1 {
-            None
-        } else {
-            let dir = (to.0 as i64 - from.0 as i64, to.1 as i64 - from.1 as i64);
-            match (dir, self.map[to.0][to.1]) {
-                ((0, 1), b'-') => Some((to.0, to.1 + 1)),
-                ((0, -1), b'-') => Some((to.0, to.1 - 1)),
-                ((1, 0), b'|') => Some((to.0 + 1, to.1)),
-                ((-1, 0), b'|') => Some((to.0 - 1, to.1)),
-                ((0, 1), b'7') => Some((to.0 + 1, to.1)),
-                ((-1, 0), b'7') => Some((to.0, to.1 - 1)),
-                ((1, 0), b'L') => Some((to.0, to.1 + 1)),
-                ((0, -1), b'L') => Some((to.0 - 1, to.1)),
-                ((0, 1), b'J') => Some((to.0 - 1, to.1)),
-                ((1, 0), b'J') => Some((to.0, to.1 - 1)),
-                ((0, -1), b'F') => Some((to.0 + 1, to.1)),
-                ((-1, 0), b'F') => Some((to.0, to.1 + 1)),
-                _ => None
-            }
+    fn move_next(&self, from: (i32, i32), to: (i32, i32)) -> Option<(i32, i32)> {
+        let dir = (to.0 - from.0, to.1 - from.1);
+        match (dir, self.map.get(to)?) {
+            ((0, 1), b'-') => Some((to.0, to.1 + 1)),
+            ((0, -1), b'-') => Some((to.0, to.1 - 1)),
+            ((1, 0), b'|') => Some((to.0 + 1, to.1)),
+            ((-1, 0), b'|') => Some((to.0 - 1, to.1)),
+            ((0, 1), b'7') => Some((to.0 + 1, to.1)),
+            ((-1, 0), b'7') => Some((to.0, to.1 - 1)),
+            ((1, 0), b'L') => Some((to.0, to.1 + 1)),
+            ((0, -1), b'L') => Some((to.0 - 1, to.1)),
+            ((0, 1), b'J') => Some((to.0 - 1, to.1)),
+            ((1, 0), b'J') => Some((to.0, to.1 - 1)),
+            ((0, -1), b'F') => Some((to.0 + 1, to.1)),
+            ((-1, 0), b'F') => Some((to.0, to.1 + 1)),
+            _ => None
         }
     }
 }
@@ -37,26 +35,43 @@ impl FromStr for Input {
     type Err = ParseError;
 
     fn from_str(input: &str) -> Result<Self, Self::Err> {
-        let mut map = Vec::new();
-        let mut start = (0, 0);
-        for (index, line) in input.lines().enumerate() {
-            if let Some(s) = line.find("S") {
-                start = (index, s);
-            }
-            map.push(line.as_bytes().to_vec());
-        }
+        let map = Grid::parse(input, "a pipe", |c| "|-LJ7F.S".contains(c).then_some(c as u8))?;
+        let start = map
+            .find(|&c| c == b'S')
+            .ok_or_else(|| ParseError::new(input, &input[input.len()..], "a start tile `S`"))?;
         Ok(Input { map, start })
     }
 }
 
+pub struct Day10;
+
+impl Solution for Day10 {
+    const DAY: u32 = 10;
+    type Input = Input;
+    type Answer1 = i64;
+    type Answer2 = i64;
+
+    fn parse(input: &str) -> Result<Input, ParseError> {
+        input.parse()
+    }
+
+    fn part1(input: Input) -> i64 {
+        part1(input)
+    }
+
+    fn part2(input: Input) -> i64 {
+        part2(input)
+    }
+}
+
 pub fn part1(input: Input) -> i64 {
     let mut steps: i64 = 2;
-    let mut current: (usize, usize) = input.start;
-    let mut next: (usize, usize) = (0, 0);
+    let mut current: (i32, i32) = input.start;
+    let mut next: (i32, i32) = (0, 0);
 
-    for dir in [(0_i64, 1_i64), (1, 0), (0, -1), (-1, 0)] {
+    for dir in [RIGHT, DOWN, LEFT, UP] {
         let start = input.start;
-        current = ((start.0 as i64 + dir.0) as usize, (start.1 as i64 + dir.1) as usize);
+        current = start + dir;
         if let Some(n) = input.move_next(start, current) {
             next = n;
             break;
@@ -76,12 +91,12 @@ pub fn part2(input: Input) -> i64 {
     let mut solution = 0_i64;
     let mut set = HashSet::new();
     set.insert(input.start);
-    let mut current: (usize, usize) = input.start;
-    let mut next: (usize, usize) = (0, 0);
+    let mut current: (i32, i32) = input.start;
+    let mut next: (i32, i32) = (0, 0);
 
-    for dir in [(0_i64, 1_i64), (1, 0), (0, -1), (-1, 0)] {
+    for dir in [RIGHT, DOWN, LEFT, UP] {
         let start = input.start;
-        current = ((start.0 as i64 + dir.0) as usize, (start.1 as i64 + dir.1) as usize);
+        current = start + dir;
         if let Some(n) = input.move_next(start, current) {
             next = n;
             break;
@@ -94,11 +109,11 @@ pub fn part2(input: Input) -> i64 {
         next = n;
     }
 
-    for (i, line) in input.map.iter().enumerate() {
+    for (i, line) in input.map.rows().enumerate() {
         let mut crossings = 0;
         let mut dir_up: bool = false;
         for (j, ch) in line.iter().enumerate() {
-            if set.contains(&(i, j)) {
+            if set.contains(&(i as i32, j as i32)) {
                 match ch {
                     b'|' => crossings += 1,
                     b'F' => dir_up = true,
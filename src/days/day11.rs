@@ -1,10 +1,12 @@
-use std::{convert::Infallible, str::FromStr};
+use std::str::FromStr;
 
-use crate::geometry::manhattan_distance_usize;
+use crate::geometry::{Grid, Point};
+use crate::days::Solution;
+use crate::parse::ParseError;
 
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, PartialEq, Eq, Clone)]
 pub struct Input {
-    galaxies: Vec<(usize, usize)>,
+    galaxies: Vec<Point<usize>>,
     empty: [Vec<usize>; 2],
 }
 
@@ -12,7 +14,7 @@ impl Input {
     fn get_distance(&self, g1: usize, g2: usize, expansion: usize) -> usize {
         let g1 = self.galaxies[g1];
         let g2 = self.galaxies[g2];
-        let mut dist = manhattan_distance_usize(g1, g2);
+        let mut dist = g1.manhattan_distance(g2);
         let get_expansion_cost = |c1: usize, c2: usize, dimension: usize| {
             self.empty[dimension]
                 .iter()
@@ -26,31 +28,43 @@ impl Input {
 }
 
 impl FromStr for Input {
-    type Err = Infallible;
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut galaxies = Vec::new();
-        let size = s.lines().count();
-        let mut empty = [Vec::new(), Vec::new()];
-        for (i, line) in s.lines().enumerate() {
-            for (j, c) in line.chars().enumerate() {
-                if c == '#' {
-                    galaxies.push((i, j));
-                }
-            }
-        }
-        for i in 0..size {
-            if !galaxies.iter().any(|&g| g.0 == i) {
-                empty[0].push(i);
-            }
-            if !galaxies.iter().any(|&g| g.1 == i) {
-                empty[1].push(i);
-            }
-        }
+        let map = Grid::parse(s, "`.` or `#`", |c| matches!(c, '.' | '#').then_some(c == '#'))?;
+        let galaxies = map
+            .iter()
+            .filter(|(_, &galaxy)| galaxy)
+            .map(|((i, j), _)| Point(i as usize, j as usize))
+            .collect();
+        let empty_rows = map.rows().enumerate().filter(|(_, row)| !row.contains(&true));
+        let empty_cols = map.columns().enumerate().filter_map(|(j, mut col)| (!col.any(|&g| g)).then_some(j));
+        let empty = [empty_rows.map(|(i, _)| i).collect(), empty_cols.collect()];
         Ok(Input { galaxies, empty })
     }
 }
 
+pub struct Day11;
+
+impl Solution for Day11 {
+    const DAY: u32 = 11;
+    type Input = Input;
+    type Answer1 = usize;
+    type Answer2 = usize;
+
+    fn parse(input: &str) -> Result<Input, ParseError> {
+        input.parse()
+    }
+
+    fn part1(input: Input) -> usize {
+        part1(input)
+    }
+
+    fn part2(input: Input) -> usize {
+        part2(input)
+    }
+}
+
 pub fn part1(input: Input) -> usize {
     sum_distances(&input, 2)
 }
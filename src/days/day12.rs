@@ -0,0 +1,180 @@
+use std::str::FromStr;
+
+use crate::days::Solution;
+use crate::parse::{self, ParseError};
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+enum Spring {
+    Operational,
+    Damaged,
+    Unknown,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+struct Row {
+    springs: Vec<Spring>,
+    groups: Vec<usize>,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Input {
+    rows: Vec<Row>,
+}
+
+impl Row {
+    /// Number of ways to replace unknown springs so the damaged ones form
+    /// exactly `groups`.
+    ///
+    /// `ways[i][j]` counts the arrangements of `springs[i..]` matching
+    /// `groups[j..]`, filled in from the end of the row.
+    fn arrangements(&self) -> usize {
+        let (n, m) = (self.springs.len(), self.groups.len());
+        // operational[i] is the number of operational springs before i, to
+        // check in O(1) whether a group fits in a window.
+        let mut operational = vec![0; n + 1];
+        for (i, &spring) in self.springs.iter().enumerate() {
+            operational[i + 1] = operational[i] + (spring == Spring::Operational) as usize;
+        }
+        let mut ways = vec![vec![0; m + 1]; n + 2];
+        ways[n][m] = 1;
+        ways[n + 1][m] = 1;
+        for i in (0..n).rev() {
+            for j in 0..=m {
+                let mut count = 0;
+                if self.springs[i] != Spring::Damaged {
+                    count += ways[i + 1][j];
+                }
+                if self.springs[i] != Spring::Operational && j < m {
+                    let end = i + self.groups[j];
+                    if end <= n
+                        && operational[end] == operational[i]
+                        && self.springs.get(end) != Some(&Spring::Damaged)
+                    {
+                        count += ways[end + 1][j + 1];
+                    }
+                }
+                ways[i][j] = count;
+            }
+        }
+        ways[0][0]
+    }
+
+    /// Repeats the row five times, joining the copies with unknown springs.
+    fn unfold(&self) -> Row {
+        let mut springs = self.springs.clone();
+        for _ in 1..5 {
+            springs.push(Spring::Unknown);
+            springs.extend(&self.springs);
+        }
+        Row { springs, groups: self.groups.repeat(5) }
+    }
+}
+
+impl FromStr for Row {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (springs, groups) = parse::split_once(s, s, " ")?;
+        let springs = springs
+            .char_indices()
+            .map(|(i, c)| match c {
+                '.' => Ok(Spring::Operational),
+                '#' => Ok(Spring::Damaged),
+                '?' => Ok(Spring::Unknown),
+                _ => Err(ParseError::new(s, &springs[i..i + c.len_utf8()], "`.`, `#` or `?`")),
+            })
+            .collect::<Result<_, _>>()?;
+        let groups = groups
+            .split(',')
+            .map(|n| match parse::number(s, n)? {
+                0 => Err(ParseError::new(s, n, "a group size above 0")),
+                size => Ok(size),
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Row { springs, groups })
+    }
+}
+
+impl FromStr for Input {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let rows = parse::lines(s, Row::from_str)?;
+        Ok(Input { rows })
+    }
+}
+
+pub struct Day12;
+
+impl Solution for Day12 {
+    const DAY: u32 = 12;
+    type Input = Input;
+    type Answer1 = usize;
+    type Answer2 = usize;
+
+    fn parse(input: &str) -> Result<Input, ParseError> {
+        input.parse()
+    }
+
+    fn part1(input: Input) -> usize {
+        part1(input)
+    }
+
+    fn part2(input: Input) -> usize {
+        part2(input)
+    }
+}
+
+pub fn part1(input: Input) -> usize {
+    input.rows.iter().map(Row::arrangements).sum()
+}
+
+pub fn part2(input: Input) -> usize {
+    input.rows.iter().map(|row| row.unfold().arrangements()).sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INPUT: &str = "\
+???.### 1,1,3
+.??..??...?##. 1,1,3
+?#?#?#?#?#?#?#? 1,3,1,6
+????.#...#... 4,1,1
+????.######..#####. 1,6,5
+?###???????? 3,2,1";
+
+    #[test]
+    fn test_part1() {
+        assert_eq!(21, part1(Input::from_str(INPUT).unwrap()));
+    }
+
+    #[test]
+    fn test_part2() {
+        assert_eq!(525152, part2(Input::from_str(INPUT).unwrap()));
+    }
+
+    #[test]
+    fn row_arrangements() {
+        let arrangements = |s| Row::from_str(s).unwrap().arrangements();
+        assert_eq!(1, arrangements("???.### 1,1,3"));
+        assert_eq!(4, arrangements(".??..??...?##. 1,1,3"));
+        assert_eq!(10, arrangements("?###???????? 3,2,1"));
+        assert_eq!(0, arrangements("### 1"));
+        assert_eq!(506250, Row::from_str("?###???????? 3,2,1").unwrap().unfold().arrangements());
+    }
+
+    #[test]
+    fn row_from_str() {
+        let expected = Row {
+            springs: vec![Spring::Unknown, Spring::Operational, Spring::Damaged],
+            groups: vec![1, 1],
+        };
+        assert_eq!(expected, Row::from_str("?.# 1,1").unwrap());
+        let e = Row::from_str("?x# 1").unwrap_err();
+        assert_eq!((2, "x"), (e.column, e.found.as_str()));
+        let e = Row::from_str("??? 1,0").unwrap_err();
+        assert_eq!(7, e.column);
+    }
+}
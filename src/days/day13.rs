@@ -1,4 +1,8 @@
-use std::{convert::Infallible, str::FromStr};
+use std::str::FromStr;
+
+use crate::days::Solution;
+use crate::geometry::Grid;
+use crate::parse::{self, ParseError};
 
 #[derive(Debug, PartialEq, Eq, Clone)]
 struct Mirror {
@@ -45,37 +49,60 @@ impl Mirror {
     }
 }
 
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, PartialEq, Eq, Clone)]
 pub struct Input {
     mirrors: Vec<Mirror>,
 }
 
 impl FromStr for Mirror {
-    type Err = Infallible;
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let rows: Vec<u32> = s.lines().map(|line| {
-            line.chars().fold(0, |acc, c| (acc << 1) | if c == '#' { 1 } else { 0 })
-        }).collect();
-
-        let size = (rows.len(), s.lines().next().unwrap().len());
-        let cols = (0..size.1).map(|col| {
-            (0..size.0).fold(0, |acc, row| (acc << 1) | ((rows[row] >> (size.1 - col - 1)) & 1))
-        }).collect();
+        let map = Grid::parse(s, "`.` or `#`", |c| matches!(c, '.' | '#').then_some(c == '#'))?;
+        if let Some(line) = s.lines().find(|line| line.len() > 32) {
+            return Err(ParseError::new(s, line, "a row of at most 32 tiles"));
+        }
+        if let Some(line) = s.lines().nth(32) {
+            return Err(ParseError::new(s, line, "at most 32 rows"));
+        }
+        let to_bits = |acc, &rock: &bool| (acc << 1) | rock as u32;
+        let rows = map.rows().map(|row| row.iter().fold(0, to_bits)).collect();
+        let cols = map.columns().map(|col| col.fold(0, to_bits)).collect();
 
-        Ok(Mirror { size, rows, cols })
+        Ok(Mirror { size: (map.height(), map.width()), rows, cols })
     }
 }
 
 impl FromStr for Input {
-    type Err = Infallible;
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mirrors = s.split("\n\n").map(|m| Mirror::from_str(m).unwrap()).collect();
+        let mirrors = parse::blocks(s, Mirror::from_str)?;
         Ok(Input { mirrors })
     }
 }
 
+pub struct Day13;
+
+impl Solution for Day13 {
+    const DAY: u32 = 13;
+    type Input = Input;
+    type Answer1 = usize;
+    type Answer2 = usize;
+
+    fn parse(input: &str) -> Result<Input, ParseError> {
+        input.parse()
+    }
+
+    fn part1(input: Input) -> usize {
+        part1(input)
+    }
+
+    fn part2(input: Input) -> usize {
+        part2(input)
+    }
+}
+
 pub fn part1(input: Input) -> usize {
     input.mirrors.into_iter().map(|m| m.symmetry_value(None).unwrap()).sum()
 }
@@ -105,6 +132,18 @@ mod tests {
 ..##..###
 #....#..#";
 
+    #[test]
+    fn mirror_size_limits() {
+        let square = vec![".".repeat(32); 32].join("\n");
+        assert_eq!((32, 32), Mirror::from_str(&square).unwrap().size);
+        let wide = vec![".".repeat(33); 32].join("\n");
+        let e = Mirror::from_str(&wide).unwrap_err();
+        assert_eq!((1, 1, "a row of at most 32 tiles"), (e.line, e.column, e.expected.as_str()));
+        let tall = vec![".".repeat(32); 33].join("\n");
+        let e = Mirror::from_str(&tall).unwrap_err();
+        assert_eq!((33, 1, "at most 32 rows"), (e.line, e.column, e.expected.as_str()));
+    }
+
     #[test]
     fn test_part1() {
         assert_eq!(405, part1(Input::from_str(INPUT).unwrap()));
@@ -1,46 +1,37 @@
-use std::{collections::HashMap, convert::Infallible, str::FromStr};
+use std::{collections::HashMap, str::FromStr};
 
 use crate::geometry::*;
+use crate::days::Solution;
+use crate::parse::ParseError;
 
 #[derive(Debug, PartialEq, Eq, Hash, Clone)]
 pub struct Input {
-    map: Vec<Vec<char>>,
-    size: usize,
+    map: Grid<char>,
 }
 
 impl Input {
     fn calculate_load(&self) -> usize {
-        let mut load = 0;
-        for i in 0..self.size {
-            for j in 0..self.size {
-                if self.map[i][j] == 'O' {
-                    load += self.size - i;
-                }
-            }
-        }
-        load
+        let height = self.map.height();
+        self.map
+            .rows()
+            .enumerate()
+            .map(|(i, row)| row.iter().filter(|&&c| c == 'O').count() * (height - i))
+            .sum()
     }
 
     fn tilt(&mut self, dir: Direction) {
-        let range: Vec<_> = if dir.0 + dir.1 == -1 {
-            (0..self.size).collect()
-        } else {
-            (0..self.size).rev().collect()
-        };
-        for i in 0..self.size {
-            for j in &range {
-                let row = i * (dir.1.abs() as usize) + j * (dir.0.abs() as usize);
-                let col = i * (dir.0.abs() as usize) + j * (dir.1.abs() as usize);
-                if self.map[row][col] == 'O' {
-                    let (mut next_r, mut next_c) = (row as i32 + dir.0, col as i32 + dir.1);
-                    while next_r >= 0 && next_r < self.size as i32 &&
-                        next_c >= 0 && next_c < self.size as i32 &&
-                        self.map[next_r as usize][next_c as usize] == '.' {
-                            (next_r, next_c) = (next_r as i32 + dir.0, next_c as i32 + dir.1);
-                    }
-                    self.map[row][col] = '.';
-                    self.map[(next_r - dir.0) as usize][(next_c - dir.1) as usize] = 'O';
+        let mut positions: Vec<_> = self.map.positions().collect();
+        if dir.0 + dir.1 == 1 {
+            positions.reverse();
+        }
+        for pos in positions {
+            if self.map[pos] == 'O' {
+                let mut next = pos + dir;
+                while self.map.get(next) == Some(&'.') {
+                    next = next + dir;
                 }
+                self.map[pos] = '.';
+                self.map[next - dir] = 'O';
             }
         }
     }
@@ -54,15 +45,32 @@ impl Input {
 }
 
 impl FromStr for Input {
-    type Err = Infallible;
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let map: Vec<Vec<char>> = s
-            .lines()
-            .map(|line| line.chars().collect())
-            .collect();
-        let rows = map.len();
-        Ok(Input { map, size: rows })
+        let map = Grid::parse(s, "`O`, `#` or `.`", |c| matches!(c, 'O' | '#' | '.').then_some(c))?;
+        Ok(Input { map })
+    }
+}
+
+pub struct Day14;
+
+impl Solution for Day14 {
+    const DAY: u32 = 14;
+    type Input = Input;
+    type Answer1 = usize;
+    type Answer2 = usize;
+
+    fn parse(input: &str) -> Result<Input, ParseError> {
+        input.parse()
+    }
+
+    fn part1(input: Input) -> usize {
+        part1(input)
+    }
+
+    fn part2(input: Input) -> usize {
+        part2(input)
     }
 }
 
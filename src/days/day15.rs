@@ -1,39 +1,86 @@
-use std::{str::FromStr, string::ParseError};
+use std::str::FromStr;
 
-#[derive(Debug, PartialEq, Eq)]
+use crate::days::Solution;
+use crate::parse::{self, ParseError};
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+enum Operation {
+    Insert(usize),
+    Remove,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+struct Step {
+    text: String,
+    label: String,
+    operation: Operation,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
 pub struct Input {
-    strings: Vec<String>
+    steps: Vec<Step>
 }
 
 impl FromStr for Input {
     type Err = ParseError;
 
     fn from_str(input: &str) -> Result<Self, Self::Err> {
-        let strings = input.split(",").map(|s| s.to_owned()).collect();
-        Ok(Input {strings})
+        let steps = input
+            .split(",")
+            .map(|step| {
+                let (label, operation) = match step.split_once("=") {
+                    Some((label, n)) => (label, Operation::Insert(parse::number(input, n)?)),
+                    None => match step.strip_suffix('-') {
+                        Some(label) => (label, Operation::Remove),
+                        None => return Err(ParseError::new(input, step, "`label=n` or `label-`")),
+                    },
+                };
+                Ok(Step { text: step.to_owned(), label: label.to_owned(), operation })
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Input {steps})
+    }
+}
+
+pub struct Day15;
+
+impl Solution for Day15 {
+    const DAY: u32 = 15;
+    type Input = Input;
+    type Answer1 = usize;
+    type Answer2 = usize;
+
+    fn parse(input: &str) -> Result<Input, ParseError> {
+        input.parse()
+    }
+
+    fn part1(input: Input) -> usize {
+        part1(input)
+    }
+
+    fn part2(input: Input) -> usize {
+        part2(input)
     }
 }
 
 pub fn part1(input: Input) -> usize {
-    input.strings.iter().map(|s| compute_hash(s)).sum()
+    input.steps.iter().map(|step| compute_hash(&step.text)).sum()
 }
 
 pub fn part2(input: Input) -> usize {
     let mut solution = 0;
     let mut boxes: [Vec<(String, usize)>; 256] = std::array::from_fn(|_| Vec::new());
-    for s in input.strings {
-        if let Some((k, n)) = s.split_once("=") {
-            let n = n.parse::<usize>().unwrap();
-            let b = &mut boxes[compute_hash(k)];
-            if let Some(ref mut focus) = b.iter_mut().find(|(s, _)| s == k) {
-                focus.1 = n;
-            } else {
-                b.push((k.to_owned(), n));
+    for Step { label: k, operation, .. } in input.steps {
+        let b = &mut boxes[compute_hash(&k)];
+        match operation {
+            Operation::Insert(n) => {
+                if let Some(ref mut focus) = b.iter_mut().find(|(s, _)| *s == k) {
+                    focus.1 = n;
+                } else {
+                    b.push((k, n));
+                }
             }
-        } else {
-            let k = s.replace("-", "");
-            let b = &mut boxes[compute_hash(&k)];
-            *b = b.iter().filter(|(s, _)| *s != k).cloned().collect();
+            Operation::Remove => b.retain(|(s, _)| *s != k),
         }
     }
     for (i, b) in boxes.iter().enumerate() {
@@ -70,6 +117,18 @@ mod tests {
         assert_eq!(3, compute_hash("pc"));
     }
 
+    #[test]
+    fn input_from_str() {
+        let input = Input::from_str("rn=1,cm-,qp= 3").unwrap();
+        let expected = vec![
+            Step { text: String::from("rn=1"), label: String::from("rn"), operation: Operation::Insert(1) },
+            Step { text: String::from("cm-"), label: String::from("cm"), operation: Operation::Remove },
+            Step { text: String::from("qp= 3"), label: String::from("qp"), operation: Operation::Insert(3) },
+        ];
+        assert_eq!(expected, input.steps);
+        assert_eq!(6, Input::from_str("rn=1,cm").unwrap_err().column);
+    }
+
     #[test]
     fn test_part1() {
         assert_eq!(1320, part1(Input::from_str(INPUT).unwrap()));
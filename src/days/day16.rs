@@ -1,13 +1,14 @@
-use std::{collections::{HashMap, HashSet, LinkedList}, str::FromStr, string::ParseError};
+use std::{collections::{HashSet, LinkedList}, str::FromStr};
 use crate::geometry::*;
+use crate::days::Solution;
+use crate::parse::ParseError;
 
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, PartialEq, Eq, Clone)]
 pub struct Input {
-    map: HashMap<(i32, i32), u8>,
-    size: (i32, i32),
+    map: Grid<u8>,
 }
 
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, PartialEq, Eq, Clone)]
 enum Action {
     Move(Direction),
     Split(Direction, Direction),
@@ -19,37 +20,39 @@ struct Beam {
     dir: Direction,
 }
 
-impl Input {
-    fn new(map: HashMap<(i32, i32), u8>, size: (i32, i32)) -> Self {
-        Input { map, size }
-    }
-
-    fn is_in(&self, pos: (i32, i32)) -> bool {
-        pos.0 >= 0 && pos.0 < self.size.0 &&
-        pos.1 >= 0 && pos.1 < self.size.0
-    }
-}
-
 impl FromStr for Input {
     type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut map = HashMap::new();
-        let size = (s.lines().count() as i32, s.lines().last().unwrap().len() as i32);
-        for (i, line) in s.lines().enumerate() {
-            for (j, c) in line.bytes().enumerate() {
-                if c != b'.' {
-                    map.insert((i as i32, j as i32), c);
-                }
-            }
-        }
-        Ok(Input::new(map, size))
+        let map = Grid::parse(s, "a mirror or splitter", |c| r".|-/\".contains(c).then_some(c as u8))?;
+        Ok(Input { map })
     }
 }
 
 impl Beam {
     fn next(&self, dir: Direction) -> Beam {
-        Beam { pos: (self.pos.0 + dir.0, self.pos.1 + dir.1), dir }
+        Beam { pos: self.pos + dir, dir }
+    }
+}
+
+pub struct Day16;
+
+impl Solution for Day16 {
+    const DAY: u32 = 16;
+    type Input = Input;
+    type Answer1 = usize;
+    type Answer2 = usize;
+
+    fn parse(input: &str) -> Result<Input, ParseError> {
+        input.parse()
+    }
+
+    fn part1(input: Input) -> usize {
+        part1(input)
+    }
+
+    fn part2(input: Input) -> usize {
+        part2(input)
     }
 }
 
@@ -58,7 +61,7 @@ pub fn part1(input: Input) -> usize {
 }
 
 pub fn part2(input: Input) -> usize {
-    get_all_starting_beams(input.size)
+    get_all_starting_beams(input.map.size())
         .into_iter()
         .map(|b| get_energized_tiles(&input, b))
         .max()
@@ -85,13 +88,14 @@ fn get_energized_tiles(input: &Input, starting_beam: Beam) -> usize {
     stack.push_back(starting_beam);
 
     while let Some(b) = stack.pop_back() {
-        if !input.is_in(b.pos) || set_beam.contains(&b){
+        let Some(&c) = input.map.get(b.pos) else {
+            continue;
+        };
+        if !set_beam.insert(b) {
             continue;
         }
-        set_beam.insert(b);
         set_pos.insert(b.pos);
-        let c = input.map.get(&b.pos).unwrap_or(&b'.');
-        match process_direction(b.dir, *c) {
+        match process_direction(b.dir, c) {
             Action::Move(d) => stack.push_back(b.next(d)),
             Action::Split(d1, d2) => {
                 stack.push_back(b.next(d1));
@@ -139,10 +143,10 @@ mod tests {
     #[test]
     fn test_input() {
         let input = Input::from_str(INPUT).unwrap();
-        assert_eq!((10, 10), input.size);
-        assert_eq!(true, input.is_in((0,0)));
-        assert_eq!(true, input.is_in((9,9)));
-        assert_eq!(false, input.is_in((0,10)));
+        assert_eq!((10, 10), input.map.size());
+        assert!(input.map.is_in((0,0)));
+        assert!(input.map.is_in((9,9)));
+        assert!(!input.map.is_in((0,10)));
     }
 
     #[test]
@@ -1,28 +1,28 @@
-use std::{cmp::Ordering, collections::{BinaryHeap, HashMap}, str::FromStr, string::ParseError, u32};
+use std::{fmt, str::FromStr};
 
 use crate::geometry::*;
+use crate::days::{Checked, Solution};
+use crate::parse::ParseError;
+use crate::pathfinding::{self, Path};
 
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, PartialEq, Eq, Clone)]
 pub struct Input {
-    map: Vec<Vec<u32>>,
-    size: (i32, i32),
+    map: Grid<u32>,
 }
 
-#[derive(Debug, PartialEq, Eq)]
+/// Where the crucible stopped and the direction it was heading; `None` only
+/// before its first move. A crucible that `stopped` short of its minimum run
+/// on a goal cannot move again.
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
 struct State {
-    heat_loss: u32,
     pos: (i32, i32),
     dir: Option<Direction>,
+    stopped: bool,
 }
 
 impl Input {
-    fn is_in(&self, pos: (i32, i32)) -> bool {
-        pos.0 >= 0 && pos.0 < self.size.0 &&
-        pos.1 >= 0 && pos.1 < self.size.1
-    }
-
     fn get_cost(&self, pos: (i32, i32)) -> u32 {
-        self.map[pos.0 as usize][pos.1 as usize]
+        self.map[pos]
     }
 }
 
@@ -30,96 +30,181 @@ impl FromStr for Input {
     type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let map = s
-            .lines()
-            .map(|line| line.chars().map(|c| c.to_digit(10).unwrap()).collect::<Vec<_>>())
-            .collect::<Vec<_>>();
-        let size = (map.len() as i32, map.first().unwrap().len() as i32);
+        let map = Grid::parse(s, "a digit", |c| c.to_digit(10))?;
 
-        Ok(Input {map, size})
+        Ok(Input {map})
     }
 }
 
-impl Ord for State {
-    fn cmp(&self, other: &Self) -> Ordering {
-        self.heat_loss.cmp(&other.heat_loss).reverse()
+/// No route the crucible can take reaches a goal.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct RouteError;
+
+impl fmt::Display for RouteError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "no route to the end")
     }
 }
 
-impl PartialOrd for State {
-    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        Some(self.cmp(other))
+impl std::error::Error for RouteError {}
+
+pub struct Day17;
+
+impl Solution for Day17 {
+    const DAY: u32 = 17;
+    type Input = Input;
+    type Answer1 = Checked<u32, RouteError>;
+    type Answer2 = Checked<u32, RouteError>;
+
+    fn parse(input: &str) -> Result<Input, ParseError> {
+        input.parse()
+    }
+
+    fn part1(input: Input) -> Checked<u32, RouteError> {
+        Checked(part1(input))
+    }
+
+    fn part2(input: Input) -> Checked<u32, RouteError> {
+        Checked(part2(input))
     }
 }
 
-pub fn part1(input: Input) -> u32 {
-    let start = (0, 0);
-    let end = (input.size.0 - 1, input.size.1 - 1);
+/// Limits on how far a crucible can move before it has to turn.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Crucible {
+    /// At most three tiles in a straight line.
+    Normal,
+    /// At least four and at most ten tiles in a straight line, including
+    /// before stopping at the end.
+    Ultra,
+}
 
-    find_min_heat_loss(input, start, end, 1, 3)
+impl Crucible {
+    /// The puzzle's rules for this crucible, from the top-left to the
+    /// bottom-right corner of `input`.
+    pub fn config(self, input: &Input) -> CrucibleConfig {
+        let (min_run, max_run) = match self {
+            Crucible::Normal => (1, 3),
+            Crucible::Ultra => (4, 10),
+        };
+        let (height, width) = input.map.size();
+        CrucibleConfig {
+            min_run,
+            max_run,
+            u_turns: false,
+            end_needs_min_run: true,
+            start: (0, 0),
+            goals: vec![(height - 1, width - 1)],
+        }
+    }
+}
+
+/// Movement rules for a crucible and where it travels between. Tweak one
+/// from `Crucible::config` with struct update syntax to ask variant
+/// questions.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct CrucibleConfig {
+    /// Tiles the crucible has to move in a straight line before turning.
+    pub min_run: i32,
+    /// Tiles the crucible can move in a straight line at most.
+    pub max_run: i32,
+    /// Whether the crucible may reverse, not just turn left or right.
+    pub u_turns: bool,
+    /// Whether the crucible has to complete `min_run` before stopping on a
+    /// goal.
+    pub end_needs_min_run: bool,
+    pub start: (i32, i32),
+    /// The crucible may stop on any of these.
+    pub goals: Vec<(i32, i32)>,
 }
 
-pub fn part2(input: Input) -> u32 {
-    let start = (0, 0);
-    let end = (input.size.0 - 1, input.size.1 - 1);
+/// Best route from the start to the nearest goal: every tile entered, in
+/// order, with the direction it was entered in.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Route {
+    pub heat_loss: u32,
+    pub moves: Vec<((i32, i32), Direction)>,
+}
 
-    find_min_heat_loss(input, start, end, 4, 10)
+pub fn part1(input: Input) -> Result<u32, RouteError> {
+    best_route(&input, &Crucible::Normal.config(&input)).map(|route| route.heat_loss).ok_or(RouteError)
 }
 
-fn find_min_heat_loss(input: Input, start: (i32, i32), end: (i32, i32), min_steps: i32, max_steps: i32) -> u32 {
-    let mut heap = BinaryHeap::new();
-    let mut dist = HashMap::new();
-    let mut best = HashMap::new();
+pub fn part2(input: Input) -> Result<u32, RouteError> {
+    best_route(&input, &Crucible::Ultra.config(&input)).map(|route| route.heat_loss).ok_or(RouteError)
+}
 
-    heap.push(State {
-        heat_loss: 0,
-        pos: start,
-        dir: None,
-    });
+pub fn best_route(input: &Input, config: &CrucibleConfig) -> Option<Route> {
+    let path = find_min_heat_loss(input, config)?;
 
-    while let Some(state) = heap.pop() {
-        for (pos, dir, cost) in get_positions(&input, state.pos, min_steps, max_steps, state.dir) {
-            let next_state = State {
-                heat_loss: state.heat_loss + cost,
-                pos,
-                dir: Some(dir),
-            };
-            let z = dist.entry((pos, dir)).or_insert(u32::MAX);
-            let x = best.entry(pos).or_insert(u32::MAX);
-            if next_state.heat_loss < *z {
-                *z = next_state.heat_loss;
-                *x = (*x).min(next_state.heat_loss);
-                heap.push(next_state);
-            }
+    let mut moves = Vec::new();
+    let mut pos = config.start;
+    for state in &path.states[1..] {
+        let dir = state.dir.expect("only the start has no direction");
+        while pos != state.pos {
+            pos = pos + dir;
+            moves.push((pos, dir));
         }
     }
+    Some(Route { heat_loss: path.cost, moves })
+}
 
-    best[&end]
+/// The heat map with the route drawn over it in arrows, as in the puzzle text.
+pub fn render(input: &Input, route: &Route) -> String {
+    let mut map = input.map.map(|&cost| char::from_digit(cost, 10).unwrap_or('?'));
+    for &(pos, dir) in &route.moves {
+        map[pos] = match dir {
+            RIGHT => '>',
+            LEFT => '<',
+            DOWN => 'v',
+            _ => '^',
+        };
+    }
+    map.to_string()
+}
+
+/// Cheapest route from the start to any goal, as the states where the
+/// crucible turned.
+fn find_min_heat_loss(input: &Input, config: &CrucibleConfig) -> Option<Path<State, u32>> {
+    if !input.map.is_in(config.start) {
+        return None;
+    }
+    pathfinding::dijkstra(
+        State { pos: config.start, dir: None, stopped: false },
+        |&state| get_positions(input, config, state),
+        |state| config.goals.contains(&state.pos),
+    )
 }
 
-fn get_possible_next_directions(direction: Option<Direction>) -> Vec<Direction> {
+fn get_possible_next_directions(direction: Option<Direction>, u_turns: bool) -> Vec<Direction> {
     match direction {
+        Some(dir) if u_turns => vec![dir.turn_left(), dir.turn_right(), dir.reverse()],
         Some(dir) => vec![dir.turn_left(), dir.turn_right()],
         None => vec![RIGHT, DOWN, LEFT, UP]
     }
 }
 
-fn get_positions(input: &Input, position: (i32, i32), min: i32, max: i32, direction: Option<Direction>) -> Vec<((i32, i32), Direction, u32)> {
+fn get_positions(input: &Input, config: &CrucibleConfig, state: State) -> Vec<(State, u32)> {
     let mut results = Vec::new();
+    if state.stopped {
+        return results;
+    }
 
-    for next_direction in get_possible_next_directions(direction) {
+    for next_direction in get_possible_next_directions(state.dir, config.u_turns) {
         let mut sum = 0;
-        let mut current_position = position;
+        let mut current_position = state.pos;
 
-        for dist in 1..=max {
-            current_position.0 += next_direction.0;
-            current_position.1 += next_direction.1;
+        for dist in 1..=config.max_run {
+            current_position = current_position + next_direction;
 
-            if !input.is_in(current_position) { break; }
+            let Some(cost) = input.map.get(current_position) else { break; };
 
-            sum += input.get_cost(current_position);
-            if dist >= min {
-                results.push((current_position, next_direction, sum));
+            sum += cost;
+            let next = State { pos: current_position, dir: Some(next_direction), stopped: false };
+            if dist >= config.min_run {
+                results.push((next, sum));
+            } else if !config.end_needs_min_run && config.goals.contains(&current_position) {
+                results.push((State { stopped: true, ..next }, sum));
             }
         }
     }
@@ -156,7 +241,7 @@ mod tests {
     #[test]
     fn test_input() {
         let input = Input::from_str(INPUT).unwrap();
-        assert_eq!((13, 13), input.size);
+        assert_eq!((13, 13), input.map.size());
         assert_eq!(2, input.get_cost((0, 0)));
         assert_eq!(8, input.get_cost((4, 7)));
         assert_eq!(3, input.get_cost((12, 12)));
@@ -165,24 +250,111 @@ mod tests {
     #[test]
     fn test_get_positions() {
         let input = Input::from_str(INPUT).unwrap();
+        let config = Crucible::Ultra.config(&input);
+        let state = |pos, dir| State { pos, dir: Some(dir), stopped: false };
         let expected = vec![
-            ((7, 11), Direction(0, 1), 27),
-            ((7, 12), Direction(0, 1), 30),
-            ((7, 3), Direction(0, -1), 29),
-            ((7, 2), Direction(0, -1), 32),
-            ((7, 1), Direction(0, -1), 38),
-            ((7, 0), Direction(0, -1), 41)];
-        assert_eq!(expected, get_positions(&input, (7,7), 4, 10, Some(DOWN)));
+            (state((7, 11), Direction(0, 1)), 27),
+            (state((7, 12), Direction(0, 1)), 30),
+            (state((7, 3), Direction(0, -1)), 29),
+            (state((7, 2), Direction(0, -1)), 32),
+            (state((7, 1), Direction(0, -1)), 38),
+            (state((7, 0), Direction(0, -1)), 41)];
+        assert_eq!(expected, get_positions(&input, &config, state((7, 7), DOWN)));
     }
 
     #[test]
     fn test_part1() {
-        assert_eq!(102, part1(Input::from_str(INPUT).unwrap()));
+        assert_eq!(Ok(102), part1(Input::from_str(INPUT).unwrap()));
     }
 
     #[test]
     fn test_part2() {
-        assert_eq!(94, part2(Input::from_str(INPUT).unwrap()));
-        assert_eq!(71, part2(Input::from_str(INPUT2).unwrap()));
+        assert_eq!(Ok(94), part2(Input::from_str(INPUT).unwrap()));
+        assert_eq!(Ok(71), part2(Input::from_str(INPUT2).unwrap()));
+        // The ultra crucible cannot stop after only three moves.
+        assert_eq!(Err(RouteError), part2(Input::from_str("1111").unwrap()));
+    }
+
+    #[test]
+    fn test_best_route() {
+        let input = Input::from_str(INPUT).unwrap();
+        let route = best_route(&input, &Crucible::Normal.config(&input)).unwrap();
+        assert_eq!(102, route.heat_loss);
+        assert_eq!(102, route.moves.iter().map(|&(pos, _)| input.get_cost(pos)).sum::<u32>());
+        assert_eq!(Some(&((12, 12), RIGHT)), route.moves.last());
+
+        let input = Input::from_str(INPUT2).unwrap();
+        let route = best_route(&input, &Crucible::Ultra.config(&input)).unwrap();
+        assert_eq!(71, route.heat_loss);
+        assert_eq!(15, route.moves.len());
+    }
+
+    #[test]
+    fn test_render() {
+        let input = Input::from_str(INPUT2).unwrap();
+        let route = best_route(&input, &Crucible::Ultra.config(&input)).unwrap();
+        let expected = "\
+1>>>>>>>1111
+9999999v9991
+9999999v9991
+9999999v9991
+9999999v>>>>";
+        assert_eq!(expected, render(&input, &route));
+    }
+
+    #[test]
+    fn test_config() {
+        let input = Input::from_str(INPUT).unwrap();
+        let config = CrucibleConfig { goals: vec![(12, 12), (0, 3)], ..Crucible::Normal.config(&input) };
+        let route = best_route(&input, &config).unwrap();
+        assert_eq!(8, route.heat_loss);
+        assert_eq!(vec![((0, 1), RIGHT), ((0, 2), RIGHT), ((0, 3), RIGHT)], route.moves);
+
+        let config = CrucibleConfig { start: (12, 12), goals: vec![(0, 0)], ..Crucible::Normal.config(&input) };
+        let route = best_route(&input, &config).unwrap();
+        assert_eq!(Some(&(0, 0)), route.moves.last().map(|(pos, _)| pos));
+        assert_eq!(route.heat_loss, route.moves.iter().map(|&(pos, _)| input.get_cost(pos)).sum::<u32>());
+
+        let config = CrucibleConfig { start: (13, 0), ..config };
+        assert_eq!(None, best_route(&input, &config));
+    }
+
+    #[test]
+    fn test_config_short_stop() {
+        let input = Input::from_str(INPUT2).unwrap();
+        let config = CrucibleConfig { goals: vec![(0, 2)], ..Crucible::Ultra.config(&input) };
+        assert!(best_route(&input, &config).unwrap().heat_loss > 2);
+
+        let config = CrucibleConfig { end_needs_min_run: false, ..config };
+        assert_eq!(2, best_route(&input, &config).unwrap().heat_loss);
+    }
+
+    #[test]
+    fn test_config_u_turns() {
+        let input = Input::from_str("1111").unwrap();
+        let config = CrucibleConfig {
+            min_run: 1,
+            max_run: 2,
+            u_turns: false,
+            end_needs_min_run: true,
+            start: (0, 0),
+            goals: vec![(0, 3)],
+        };
+        assert_eq!(None, best_route(&input, &config));
+
+        let config = CrucibleConfig { u_turns: true, ..config };
+        let route = best_route(&input, &config).unwrap();
+        assert_eq!(5, route.heat_loss);
+        assert_eq!(vec![((0, 1), RIGHT), ((0, 2), RIGHT), ((0, 1), LEFT), ((0, 2), RIGHT), ((0, 3), RIGHT)], route.moves);
+    }
+
+    #[test]
+    fn test_find_min_heat_loss() {
+        let input = Input::from_str(INPUT2).unwrap();
+        let path = find_min_heat_loss(&input, &Crucible::Ultra.config(&input)).unwrap();
+        let turns: Vec<_> = path.states.iter().map(|state| (state.pos, state.dir)).collect();
+        let expected = vec![((0, 0), None), ((0, 7), Some(RIGHT)), ((4, 7), Some(DOWN)), ((4, 11), Some(RIGHT))];
+        assert_eq!(71, path.cost);
+        assert_eq!(expected, turns);
     }
 }
@@ -1,6 +1,8 @@
-use std::{str::FromStr, string::ParseError};
+use std::str::FromStr;
 
 use crate::geometry::*;
+use crate::days::Solution;
+use crate::parse::{self, ParseError};
 
 #[derive(Debug, PartialEq, Eq, Clone)]
 struct Entry {
@@ -14,38 +16,68 @@ pub struct Input {
     actual_entries: Vec<Entry>,
 }
 
-fn get_entry_part1(s: &str) -> Entry {
-    let mut split = s.split_whitespace();
-    let (Some(dir), len, _) = (split.next(), split.next().unwrap().parse::<i64>().unwrap(), split.next()) else { unreachable!() };
+fn get_entry_part1(s: &str) -> Result<Entry, ParseError> {
+    let (dir, rest) = parse::split_once(s, s, " ")?;
+    let (len, _) = parse::split_once(s, rest, " ")?;
+    let len = parse::number(s, len)?;
     let dir = match dir {
         "R" => RIGHT,
         "L" => LEFT,
         "D" => DOWN,
         "U" => UP,
-        _ => unreachable!(),
+        _ => return Err(ParseError::new(s, dir, "`R`, `L`, `D` or `U`")),
     };
 
-    Entry {dir, len}
+    Ok(Entry {dir, len})
 }
 
-fn get_entry_part2(s: &str) -> Entry {
+fn get_entry_part2(s: &str) -> Result<Entry, ParseError> {
     const DIRECTIONS: [Direction; 4] = [RIGHT, DOWN, LEFT, UP];
-    let instruction = s.split_once('#').unwrap().1;
-    let dir = DIRECTIONS[(instruction.as_bytes()[5] as char).to_digit(10).unwrap() as usize];
-    let len = i64::from_str_radix(&instruction[0..5], 16).unwrap();
-    Entry {dir, len}
+    let instruction = parse::split_once(s, s, "#")?.1.trim_end_matches(')');
+    let (len, dir) = match (instruction.get(0..5), instruction.get(5..)) {
+        (Some(len), Some(dir)) if dir.len() == 1 => (len, dir),
+        _ => return Err(ParseError::new(s, instruction, "six hexadecimal digits")),
+    };
+    let len = i64::from_str_radix(len, 16)
+        .map_err(|_| ParseError::new(s, len, "five hexadecimal digits"))?;
+    let dir = match dir {
+        "0" | "1" | "2" | "3" => DIRECTIONS[parse::number::<usize>(s, dir)?],
+        _ => return Err(ParseError::new(s, dir, "a direction digit between 0 and 3")),
+    };
+    Ok(Entry {dir, len})
 }
 
 impl FromStr for Input {
     type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let entries = s.lines().map(get_entry_part1).collect();
-        let actual_entries = s.lines().map(get_entry_part2).collect();
+        let entries = parse::lines(s, get_entry_part1)?;
+        let actual_entries = parse::lines(s, get_entry_part2)?;
         Ok(Input {entries, actual_entries})
     }
 }
 
+pub struct Day18;
+
+impl Solution for Day18 {
+    const DAY: u32 = 18;
+    type Input = Input;
+    type Answer1 = i64;
+    type Answer2 = i64;
+
+    fn parse(input: &str) -> Result<Input, ParseError> {
+        input.parse()
+    }
+
+    fn part1(input: Input) -> i64 {
+        part1(input)
+    }
+
+    fn part2(input: Input) -> i64 {
+        part2(input)
+    }
+}
+
 pub fn part1(input: Input) -> i64 {
     solve(&input.entries)
 }
@@ -54,12 +86,12 @@ pub fn part2(input: Input) -> i64 {
     solve(&input.actual_entries)
 }
 
-fn solve(entries: &Vec<Entry>) -> i64 {
+fn solve(entries: &[Entry]) -> i64 {
     let polygon = get_polygon_vertices(entries);
     calculate_area(&polygon) + calculate_perimeter(entries) / 2 + 1
 }
 
-fn get_polygon_vertices(entries: &Vec<Entry>) -> Vec<(i64, i64)> {
+fn get_polygon_vertices(entries: &[Entry]) -> Vec<(i64, i64)> {
     let mut vertices = Vec::new();
     let mut current_vertex = (0, 0);
 
@@ -75,14 +107,14 @@ fn get_polygon_vertices(entries: &Vec<Entry>) -> Vec<(i64, i64)> {
 }
 
 /// https://en.wikipedia.org/wiki/Shoelace_formula
-fn calculate_area(polygon: &Vec<(i64, i64)>) -> i64 {
+fn calculate_area(polygon: &[(i64, i64)]) -> i64 {
     polygon.windows(2)
         .map(|w| (w[0].0 - w[1].0) * (w[0].1 + w[1].1))
         .sum::<i64>()
         .abs() / 2
 }
 
-fn calculate_perimeter(entries: &Vec<Entry>) -> i64 {
+fn calculate_perimeter(entries: &[Entry]) -> i64 {
     entries.iter()
         .map(|e| e.len)
         .sum()
@@ -110,8 +142,9 @@ U 2 (#7a21e3)";
 
     #[test]
     fn entry_from_str() {
-        assert_eq!(Entry {dir: RIGHT, len: 6}, get_entry_part1("R 6 (#70c710)"));
-        assert_eq!(Entry {dir: RIGHT, len: 0x70c71}, get_entry_part2("R 6 (#70c710)"));
+        assert_eq!(Ok(Entry {dir: RIGHT, len: 6}), get_entry_part1("R 6 (#70c710)"));
+        assert_eq!(Ok(Entry {dir: RIGHT, len: 0x70c71}), get_entry_part2("R 6 (#70c710)"));
+        assert_eq!(12, get_entry_part2("R 6 (#70c714)").unwrap_err().column);
     }
 
     #[test]
@@ -1,24 +1,28 @@
-use std::{collections::HashMap, convert::Infallible, str::FromStr};
+use std::{collections::HashMap, str::FromStr};
 
-#[derive(Debug, PartialEq, Eq)]
+use crate::days::Solution;
+use crate::dot::{Graph, ToDot};
+use crate::parse::{self, ParseError};
+
+#[derive(Debug, PartialEq, Eq, Clone)]
 struct RuleSet {
     name: String,
     rules: Vec<Rule>,
 }
 
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, PartialEq, Eq, Clone)]
 enum Rule {
     LessThan(usize, i32, String),
     GreaterThan(usize, i32, String),
     Goto(String),
 }
 
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, PartialEq, Eq, Clone)]
 struct Part {
     ratings: [i32; 4],
 }
 
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, PartialEq, Eq, Clone)]
 pub struct Input {
     rulesets: HashMap<String, RuleSet>,
     parts: Vec<Part>,
@@ -40,28 +44,35 @@ impl RatingsRange {
 }
 
 impl FromStr for Part {
-    type Err = Infallible;
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut iter = s
+        let ratings = s
             .trim_matches(['{', '}'])
             .split(',')
-            .map(|rating| rating.split_once('=').unwrap().1.parse::<i32>().unwrap());
-        let ratings = std::array::from_fn(|_| iter.next().unwrap());
+            .map(|rating| parse::split_once(s, rating, "=").map(|(_, n)| n))
+            .collect::<Result<Vec<_>, _>>()?;
+        let ratings = parse::numbers(s, ratings)?;
         Ok(Part { ratings })
     }
 }
 
 impl FromStr for Rule {
-    type Err = Infallible;
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let rule = match s.chars().nth(1) {
-            Some(x) if x == '<' || x == '>' => {
-                let i = s.as_bytes()[0];
-                let i = if i == b'a' { 2 } else { i % 4 } as usize;
-                let (n, to) = s[2..].split_once(':').unwrap();
-                let n = n.parse().unwrap();
+        let mut chars = s.chars();
+        let rule = match (chars.next(), chars.next()) {
+            (Some(c), Some(x)) if x == '<' || x == '>' => {
+                let i = match c {
+                    'x' => 0,
+                    'm' => 1,
+                    'a' => 2,
+                    's' => 3,
+                    _ => return Err(ParseError::new(s, &s[..c.len_utf8()], "`x`, `m`, `a` or `s`")),
+                };
+                let (n, to) = parse::split_once(s, &s[c.len_utf8() + 1..], ":")?;
+                let n = parse::number(s, n)?;
                 if x == '<' {
                     Rule::LessThan(i, n, to.to_owned())
                 } else {
@@ -75,39 +86,80 @@ impl FromStr for Rule {
 }
 
 impl FromStr for RuleSet {
-    type Err = Infallible;
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let (name, rest) = s.split_once('{').unwrap();
+        let (name, rest) = parse::split_once(s, s, "{")?;
         let rules = rest
             .trim_end_matches('}')
             .split(',')
-            .map(|rule| Rule::from_str(rule).unwrap())
-            .collect();
+            .map(|rule| Rule::from_str(rule).map_err(|e| e.within(s, rule)))
+            .collect::<Result<_, _>>()?;
         Ok(RuleSet { name: name.to_owned(), rules})
     }
 }
 
 impl FromStr for Input {
-    type Err = Infallible;
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let (rulesets, parts) = s.split_once("\n\n").unwrap();
-        let rulesets = rulesets
-            .lines()
-            .map(|rs| {
-                let ruleset = RuleSet::from_str(rs).unwrap();
-                (ruleset.name.clone(), ruleset)
-            })
-            .collect();
-        let parts = parts
-            .lines()
-            .map(|part| Part::from_str(part).unwrap())
+        let (rulesets, parts) = parse::split_once(s, s, "\n\n")?;
+        let rulesets = parse::lines(rulesets, RuleSet::from_str)
+            .map_err(|e| e.within(s, rulesets))?
+            .into_iter()
+            .map(|ruleset| (ruleset.name.clone(), ruleset))
             .collect();
+        let parts = parse::lines(parts, Part::from_str).map_err(|e| e.within(s, parts))?;
         Ok(Input { rulesets, parts })
     }
 }
 
+impl ToDot for Input {
+    /// Workflows linked by their rules, in order, each edge labeled with the
+    /// condition that has to hold (on top of earlier rules failing).
+    fn to_dot(&self) -> Graph {
+        const CATEGORIES: [char; 4] = ['x', 'm', 'a', 's'];
+        let mut graph = Graph::directed("day 19");
+        graph.node("A", &[("shape", "doublecircle"), ("label", "accepted")]);
+        graph.node("R", &[("shape", "doublecircle"), ("label", "rejected")]);
+        let mut names: Vec<_> = self.rulesets.keys().collect();
+        names.sort();
+        for name in names {
+            graph.node(name, &[("shape", "box")]);
+            for rule in &self.rulesets[name].rules {
+                let (label, to) = match rule {
+                    Rule::LessThan(i, n, to) => (format!("{}<{n}", CATEGORIES[*i]), to),
+                    Rule::GreaterThan(i, n, to) => (format!("{}>{n}", CATEGORIES[*i]), to),
+                    Rule::Goto(to) => (String::from("else"), to),
+                };
+                graph.edge(name, to, &[("label", &label)]);
+            }
+        }
+        graph
+    }
+}
+
+pub struct Day19;
+
+impl Solution for Day19 {
+    const DAY: u32 = 19;
+    type Input = Input;
+    type Answer1 = i32;
+    type Answer2 = i64;
+
+    fn parse(input: &str) -> Result<Input, ParseError> {
+        input.parse()
+    }
+
+    fn part1(input: Input) -> i32 {
+        part1(input)
+    }
+
+    fn part2(input: Input) -> i64 {
+        part2(input)
+    }
+}
+
 pub fn part1(input: Input) -> i32 {
     let mut sol = 0;
     for part in input.parts {
@@ -213,6 +265,7 @@ hdj{m>838:A,pv}
         assert_eq!(Rule::LessThan(2, 2006, String::from("qkq")), Rule::from_str("a<2006:qkq").unwrap());
         assert_eq!(Rule::GreaterThan(1, 2090, String::from("A")), Rule::from_str("m>2090:A").unwrap());
         assert_eq!(Rule::Goto(String::from("rfg")), Rule::from_str("rfg").unwrap());
+        assert_eq!("é", Rule::from_str("é<2006:qkq").unwrap_err().found);
     }
 
     #[test]
@@ -243,6 +296,13 @@ hdj{m>838:A,pv}
         assert_eq!(1, range2.count_combinations());
     }
 
+    #[test]
+    fn input_to_dot() {
+        let dot = Input::from_str(INPUT).unwrap().to_dot().to_string();
+        assert!(dot.contains("    \"in\" [shape=\"box\"];\n    \"in\" -> \"px\" [label=\"s<1351\"];\n    \"in\" -> \"qqz\" [label=\"else\"];"));
+        assert!(dot.contains("    \"px\" -> \"A\" [label=\"m>2090\"];"));
+    }
+
     #[test]
     fn test_part1() {
         assert_eq!(19114, part1(Input::from_str(INPUT).unwrap()));
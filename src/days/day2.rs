@@ -1,47 +1,147 @@
-use std::{cmp::max, collections::HashMap};
-
-pub fn part1(input: String) -> i32 {
-    let mut solution = 0;
-    for line in input.lines() {
-        let (game, rounds) = line.split_once(": ").unwrap();
-        let id = game.split_once(" ").unwrap().1.parse::<i32>().unwrap();
-        let is_valid = rounds
-            .split("; ")
-            .flat_map(|s| s.split(", "))
-            .map(|s| {
-                let (n, c) = s.split_once(" ").unwrap();
-                (n.parse::<i32>().unwrap(), c)
-            })
-            .all(|(n, color)| {
-                matches!(
-                    (n, color),
-                    (0..=12, "red") | (0..=13, "green") | (0..=14, "blue")
-                )
-            });
-        solution += if is_valid { id } else { 0 };
-    }
-    solution
+use std::{collections::BTreeMap, str::FromStr};
+
+use crate::days::Solution;
+use crate::parse::{self, ParseError};
+
+/// One handful of cubes: how many of each colour were shown.
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
+pub struct Draw {
+    pub cubes: BTreeMap<String, i32>,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Game {
+    pub id: i32,
+    pub draws: Vec<Draw>,
+}
+
+/// Number of cubes of each colour in the bag; colours not listed have none.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Bag {
+    pub limits: BTreeMap<String, i32>,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Input {
+    games: Vec<Game>,
+}
+
+impl Bag {
+    pub fn new<'a>(limits: impl IntoIterator<Item = (&'a str, i32)>) -> Self {
+        let limits = limits.into_iter().map(|(colour, n)| (colour.to_owned(), n)).collect();
+        Bag { limits }
+    }
+
+    pub fn limit(&self, colour: &str) -> i32 {
+        self.limits.get(colour).copied().unwrap_or(0)
+    }
+
+    pub fn power(&self) -> i32 {
+        self.limits.values().product()
+    }
+}
+
+impl Default for Bag {
+    /// The bag from the puzzle: 12 red, 13 green and 14 blue cubes.
+    fn default() -> Self {
+        Bag::new([("red", 12), ("green", 13), ("blue", 14)])
+    }
+}
+
+impl Game {
+    pub fn is_possible(&self, bag: &Bag) -> bool {
+        self.draws
+            .iter()
+            .flat_map(|draw| &draw.cubes)
+            .all(|(colour, &n)| n <= bag.limit(colour))
+    }
+
+    /// Smallest bag that could have produced every draw of the game.
+    pub fn minimum_bag(&self) -> Bag {
+        let mut limits = BTreeMap::new();
+        for (colour, &n) in self.draws.iter().flat_map(|draw| &draw.cubes) {
+            let limit = limits.entry(colour.clone()).or_insert(0);
+            *limit = n.max(*limit);
+        }
+        Bag { limits }
+    }
+}
+
+impl FromStr for Draw {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut draw = Draw::default();
+        for cube in s.split(", ") {
+            let (n, colour) = parse::split_once(s, cube, " ")?;
+            if colour.is_empty() || !colour.chars().all(|c| c.is_ascii_alphabetic()) {
+                return Err(ParseError::new(s, colour, "a colour"));
+            }
+            *draw.cubes.entry(colour.to_owned()).or_insert(0) += parse::number::<i32>(s, n)?;
+        }
+        Ok(draw)
+    }
 }
 
-pub fn part2(input: String) -> i32 {
-    let mut solution = 0;
-    for line in input.lines() {
-        let mut max_color_values = HashMap::new();
-        let (_, rounds) = line.split_once(": ").unwrap();
-        rounds
+impl FromStr for Game {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (id, draws) = parse::split_once(s, s, ": ")?;
+        let id = parse::number(s, parse::split_once(s, id, " ")?.1)?;
+        let draws = draws
             .split("; ")
-            .flat_map(|s| s.split(", "))
-            .for_each(|s| {
-                let (n, c) = s.split_once(" ").unwrap();
-                let n = n.parse().unwrap();
-                max_color_values
-                    .entry(c)
-                    .and_modify(|m| *m = max(*m, n))
-                    .or_insert(n);
-            });
-        solution += max_color_values.values().product::<i32>();
-    }
-    solution
+            .map(|draw| Draw::from_str(draw).map_err(|e| e.within(s, draw)))
+            .collect::<Result<_, _>>()?;
+        Ok(Game { id, draws })
+    }
+}
+
+impl FromStr for Input {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let games = parse::lines(s, Game::from_str)?;
+        Ok(Input { games })
+    }
+}
+
+pub struct Day2;
+
+impl Solution for Day2 {
+    const DAY: u32 = 2;
+    type Input = Input;
+    type Answer1 = i32;
+    type Answer2 = i32;
+
+    fn parse(input: &str) -> Result<Input, ParseError> {
+        input.parse()
+    }
+
+    fn part1(input: Input) -> i32 {
+        part1(input)
+    }
+
+    fn part2(input: Input) -> i32 {
+        part2(input)
+    }
+}
+
+pub fn part1(input: Input) -> i32 {
+    sum_possible_ids(&input, &Bag::default())
+}
+
+pub fn part2(input: Input) -> i32 {
+    input.games.iter().map(|game| game.minimum_bag().power()).sum()
+}
+
+/// Sum of the ids of the games that could have been played with `bag`.
+pub fn sum_possible_ids(input: &Input, bag: &Bag) -> i32 {
+    input.games
+        .iter()
+        .filter(|game| game.is_possible(bag))
+        .map(|game| game.id)
+        .sum()
 }
 
 #[cfg(test)]
@@ -55,13 +155,41 @@ Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red
 Game 4: 1 green, 3 red, 6 blue; 3 green, 6 red; 3 green, 15 blue, 14 red
 Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green";
 
+    #[test]
+    fn game_from_str() {
+        let draw = |cubes: &[(&str, i32)]| Draw {
+            cubes: cubes.iter().map(|&(colour, n)| (colour.to_owned(), n)).collect(),
+        };
+        let expected = Game {
+            id: 1,
+            draws: vec![
+                draw(&[("blue", 3), ("red", 4)]),
+                draw(&[("red", 1), ("green", 2), ("blue", 6)]),
+                draw(&[("green", 2)]),
+            ],
+        };
+        assert_eq!(expected, Game::from_str("Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green").unwrap());
+        let e = Game::from_str("Game 1: 3 blue; 4 r3d").unwrap_err();
+        assert_eq!((19, "r3d"), (e.column, e.found.as_str()));
+    }
+
     #[test]
     fn test_part1() {
-        assert_eq!(8, part1(INPUT.to_owned()));
+        assert_eq!(8, part1(Input::from_str(INPUT).unwrap()));
     }
 
     #[test]
     fn test_part2() {
-        assert_eq!(2286, part2(INPUT.to_owned()));
+        assert_eq!(2286, part2(Input::from_str(INPUT).unwrap()));
+    }
+
+    #[test]
+    fn custom_bags() {
+        let input = Input::from_str("Game 1: 2 teal, 1 red\nGame 2: 3 teal; 1 mauve").unwrap();
+        assert_eq!(1, sum_possible_ids(&input, &Bag::new([("teal", 2), ("red", 1)])));
+        assert_eq!(3, sum_possible_ids(&input, &Bag::new([("teal", 3), ("red", 1), ("mauve", 1)])));
+        assert_eq!(0, sum_possible_ids(&input, &Bag::default()));
+        assert_eq!(Bag::new([("mauve", 1), ("teal", 3)]), input.games[1].minimum_bag());
+        assert_eq!(5, part2(input));
     }
 }
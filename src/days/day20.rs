@@ -1,6 +1,9 @@
-use std::{collections::{HashMap, VecDeque}, convert::Infallible, str::FromStr};
+use std::{collections::{HashMap, VecDeque}, fmt, str::FromStr};
 
 use crate::math;
+use crate::days::{Checked, Solution};
+use crate::dot::{Graph, ToDot};
+use crate::parse::{self, ParseError};
 
 #[derive(Debug, PartialEq, Eq, Hash, Clone)]
 enum ModuleType {
@@ -14,116 +17,339 @@ struct Module {
     t: ModuleType,
     name: String,
     next: Vec<String>,
-    on: bool,
-    previous: HashMap<String, bool>,
 }
 
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, PartialEq, Eq, Clone)]
 pub struct Input {
     modules: HashMap<String, Module>,
 }
 
-impl Module {
-    fn process_pulse(&mut self, from: String, value: bool) -> Option<bool> {
-        match self.t {
-            ModuleType::Broadcaster => Some(value),
-            ModuleType::FlipFlop if value => None,
-            ModuleType::FlipFlop => {
-                self.on = !self.on;
-                Some(self.on)
-            },
-            ModuleType::Conjunction => {
-                self.previous.entry(from).and_modify(|v| *v = value);
-                Some(!self.previous.values().all(|v| *v))
+/// A pulse sent during the `press`-th button press (counting from 1).
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Pulse {
+    pub from: usize,
+    pub to: usize,
+    pub high: bool,
+    pub press: usize,
+}
+
+/// Flip-flop and conjunction memories, enough to replay the network from.
+#[derive(Debug, PartialEq, Eq, Hash, Clone)]
+pub struct State {
+    on: Vec<bool>,
+    memory: Vec<Vec<bool>>,
+}
+
+#[derive(Debug, PartialEq, Eq, Hash, Clone)]
+pub struct Snapshot {
+    pub presses: usize,
+    pub state: State,
+}
+
+/// Pulse network simulator with modules numbered by id. Id 0 is the button,
+/// and modules that only receive pulses (like `rx`) get ids too.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Simulator {
+    names: Vec<String>,
+    kinds: Vec<Option<ModuleType>>,
+    /// Destinations of every module with the input slot the pulse lands in.
+    next: Vec<Vec<(usize, usize)>>,
+    broadcaster: usize,
+    presses: usize,
+    state: State,
+}
+
+impl Simulator {
+    pub const BUTTON: usize = 0;
+
+    pub fn new(input: &Input) -> Self {
+        let mut modules: Vec<_> = input.modules.values().collect();
+        modules.sort_by(|a, b| a.name.cmp(&b.name));
+        let mut names = vec![String::from("button")];
+        names.extend(modules.iter().map(|m| m.name.clone()));
+        for m in &modules {
+            for n in &m.next {
+                if !names.contains(n) {
+                    names.push(n.clone());
+                }
             }
         }
+        let ids: HashMap<_, _> = names.iter().enumerate().map(|(id, name)| (name.clone(), id)).collect();
+        let mut kinds = vec![None; names.len()];
+        let mut next = vec![Vec::new(); names.len()];
+        let mut memory = vec![Vec::new(); names.len()];
+        for m in &modules {
+            kinds[ids[&m.name]] = Some(m.t.clone());
+        }
+        for m in &modules {
+            for n in &m.next {
+                let to = ids[n];
+                next[ids[&m.name]].push((to, memory[to].len()));
+                memory[to].push(false);
+            }
+        }
+        let broadcaster = ids.get("broadcaster").copied().unwrap_or(Self::BUTTON);
+        let state = State { on: vec![false; names.len()], memory };
+        Simulator { names, kinds, next, broadcaster, presses: 0, state }
+    }
+
+    pub fn id(&self, name: &str) -> Option<usize> {
+        self.names.iter().position(|n| n == name)
+    }
+
+    pub fn name(&self, id: usize) -> &str {
+        &self.names[id]
+    }
+
+    /// Ids of the modules sending pulses to `id`.
+    pub fn inputs(&self, id: usize) -> Vec<usize> {
+        (0..self.names.len())
+            .filter(|&from| self.next[from].iter().any(|&(to, _)| to == id))
+            .collect()
     }
-}
 
-impl Input {
-    fn press_button(&mut self, monitor: &str) -> (usize, usize, bool) {
-        let mut q = VecDeque::new();
-        let mut lo = 0;
-        let mut hi = 0;
-        let mut triggered = false;
-        q.push_back((String::new(), String::from("broadcaster"), false));
-        while let Some((from, to, pulse)) = q.pop_front() {
-            if pulse { hi += 1; } else { lo += 1; }
-            if let Some(module) = self.modules.get_mut(&to) {
-                if pulse && to == monitor {
-                    triggered = true;
+    pub fn presses(&self) -> usize {
+        self.presses
+    }
+
+    /// Presses the button once, handing every pulse to each watcher in the
+    /// order pulses are processed.
+    pub fn press(&mut self, watchers: &mut [&mut dyn FnMut(&Pulse)]) {
+        self.presses += 1;
+        let mut queue = VecDeque::new();
+        queue.push_back((Self::BUTTON, self.broadcaster, usize::MAX, false));
+        while let Some((from, to, slot, high)) = queue.pop_front() {
+            let pulse = Pulse { from, to, high, press: self.presses };
+            for watch in watchers.iter_mut() {
+                watch(&pulse);
+            }
+            let out = match self.kinds[to] {
+                None => None,
+                Some(ModuleType::Broadcaster) => Some(high),
+                Some(ModuleType::FlipFlop) if high => None,
+                Some(ModuleType::FlipFlop) => {
+                    self.state.on[to] = !self.state.on[to];
+                    Some(self.state.on[to])
                 }
-                if let Some(next_pulse) = module.process_pulse(from, pulse) {
-                    q.extend(module.next.iter().map(|nm| (to.clone(), nm.clone(), next_pulse)));
+                Some(ModuleType::Conjunction) => {
+                    self.state.memory[to][slot] = high;
+                    Some(!self.state.memory[to].iter().all(|&v| v))
                 }
+            };
+            if let Some(out) = out {
+                queue.extend(self.next[to].iter().map(|&(next, slot)| (to, next, slot, out)));
+            }
+        }
+    }
+
+    /// Presses the button once and returns every pulse sent.
+    pub fn pulses(&mut self) -> Vec<Pulse> {
+        let mut pulses = Vec::new();
+        self.press(&mut [&mut |p: &Pulse| pulses.push(*p)]);
+        pulses
+    }
+
+    pub fn snapshot(&self) -> Snapshot {
+        Snapshot { presses: self.presses, state: self.state.clone() }
+    }
+
+    pub fn restore(&mut self, snapshot: &Snapshot) {
+        self.presses = snapshot.presses;
+        self.state = snapshot.state.clone();
+    }
+
+    /// Presses the button until the whole network returns to a state it was
+    /// in before, giving the press count the cycle starts at and its length.
+    pub fn find_cycle(&mut self, max_presses: usize) -> Option<(usize, usize)> {
+        let mut seen = HashMap::from([(self.state.clone(), self.presses)]);
+        for _ in 0..max_presses {
+            self.press(&mut []);
+            if let Some(&start) = seen.get(&self.state) {
+                return Some((start, self.presses - start));
             }
+            seen.insert(self.state.clone(), self.presses);
         }
-        (lo, hi, triggered)
+        None
     }
 }
 
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum CycleError {
+    NoFeeder,
+    ManyFeeders(Vec<String>),
+    NotConjunction(String),
+    NoInputs(String),
+    NoCycle { input: String, presses: usize },
+    Offset { input: String, hits: Vec<usize> },
+}
+
+impl fmt::Display for CycleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CycleError::NoFeeder => write!(f, "no module sends pulses to `rx`"),
+            CycleError::ManyFeeders(names) => write!(f, "`rx` is fed by several modules: {}", names.join(", ")),
+            CycleError::NotConjunction(name) => write!(f, "`{name}` feeding `rx` is not a conjunction"),
+            CycleError::NoInputs(name) => write!(f, "`{name}` feeding `rx` has no inputs"),
+            CycleError::NoCycle { input, presses } => {
+                write!(f, "`{input}` did not send enough high pulses in {presses} presses to find a cycle")
+            }
+            CycleError::Offset { input, hits } => {
+                write!(f, "high pulses from `{input}` at presses {hits:?} are not a cycle starting at 0")
+            }
+        }
+    }
+}
+
+impl std::error::Error for CycleError {}
+
 impl FromStr for Module {
-    type Err = Infallible;
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let (module, next) = s.split_once(" -> ").unwrap();
-        let (t, name) = match module.as_bytes() {
-            [b'%', rest @ ..] => (ModuleType::FlipFlop, String::from_utf8_lossy(rest).into_owned()),
-            [b'&', rest @ ..] => (ModuleType::Conjunction, String::from_utf8_lossy(rest).into_owned()),
-            _ => (ModuleType::Broadcaster, String::from("broadcaster")),
+        let (module, next) = parse::split_once(s, s, " -> ")?;
+        let (t, name) = if let Some(name) = module.strip_prefix('%') {
+            (ModuleType::FlipFlop, name)
+        } else if let Some(name) = module.strip_prefix('&') {
+            (ModuleType::Conjunction, name)
+        } else if module == "broadcaster" {
+            (ModuleType::Broadcaster, module)
+        } else {
+            return Err(ParseError::new(s, module, "`broadcaster`, `%name` or `&name`"));
         };
+        if name.is_empty() {
+            return Err(ParseError::new(s, module, "a module name"));
+        }
         let next = next
             .split(", ")
-            .map(|n| String::from(n))
-            .collect();
-        Ok(Module { t, name, next, on: false, previous: HashMap::new() })
+            .map(|n| match n {
+                "" => Err(ParseError::new(s, n, "a module name")),
+                n => Ok(String::from(n)),
+            })
+            .collect::<Result<_, _>>()?;
+        let name = name.to_owned();
+        Ok(Module { t, name, next })
     }
 }
 
 impl FromStr for Input {
-    type Err = Infallible;
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut modules: HashMap<_,_> = s.lines()
-            .map(|line| Module::from_str(line).unwrap())
-            .map(|m| (m.name.clone(), m))
-            .collect();
-        for m in modules.clone().values() {
-            for n in &m.next {
-                if let Some(nm) = modules.get_mut(n) {
-                    if nm.t == ModuleType::Conjunction {
-                        nm.previous.insert(m.name.clone(), false);
-                    }
-                }
+        let mut modules = HashMap::new();
+        for (line, module) in s.lines().zip(parse::lines(s, Module::from_str)?) {
+            if modules.contains_key(&module.name) {
+                let (token, _) = parse::split_once(line, line, " -> ")?;
+                return Err(ParseError::new(s, token, "a module defined only once"));
             }
+            modules.insert(module.name.clone(), module);
         }
         Ok(Input { modules })
     }
 }
 
+impl ToDot for Input {
+    /// Modules shaped by type: flip-flops as boxes, conjunctions as diamonds.
+    fn to_dot(&self) -> Graph {
+        let mut graph = Graph::directed("day 20");
+        let mut modules: Vec<_> = self.modules.values().collect();
+        modules.sort_by(|a, b| a.name.cmp(&b.name));
+        for m in modules {
+            let (shape, label) = match m.t {
+                ModuleType::Broadcaster => ("doublecircle", m.name.clone()),
+                ModuleType::FlipFlop => ("box", format!("%{}", m.name)),
+                ModuleType::Conjunction => ("diamond", format!("&{}", m.name)),
+            };
+            graph.node(&m.name, &[("shape", shape), ("label", &label)]);
+            for next in &m.next {
+                graph.edge(&m.name, next, &[]);
+            }
+        }
+        graph
+    }
+}
+
+pub struct Day20;
+
+impl Solution for Day20 {
+    const DAY: u32 = 20;
+    type Input = Input;
+    type Answer1 = usize;
+    type Answer2 = Checked<i64, CycleError>;
+
+    fn parse(input: &str) -> Result<Input, ParseError> {
+        input.parse()
+    }
+
+    fn part1(input: Input) -> usize {
+        part1(input)
+    }
+
+    fn part2(input: Input) -> Checked<i64, CycleError> {
+        Checked(part2(input))
+    }
+}
+
 pub fn part1(input: Input) -> usize {
-    let mut input = input;
-    let (mut lo, mut hi) = (0, 0);
+    let mut simulator = Simulator::new(&input);
+    let mut counts = [0, 0];
     for _ in 0..1000 {
-        let out = input.press_button("");
-        lo += out.0;
-        hi += out.1;
+        simulator.press(&mut [&mut |p: &Pulse| counts[p.high as usize] += 1]);
     }
-    lo * hi
+    counts[0] * counts[1]
 }
 
-pub fn part2(input: Input) -> i64 {
-    let mut input = input;
+pub fn part2(input: Input) -> Result<i64, CycleError> {
+    const MAX_PRESSES: usize = 100_000;
+    const HITS: usize = 3;
+
+    let name = find_feeder(&input, "rx")?;
+    let mut simulator = Simulator::new(&input);
+    let feeder = simulator.id(&name).unwrap();
+    let inputs = simulator.inputs(feeder);
+    if inputs.is_empty() {
+        return Err(CycleError::NoInputs(name));
+    }
+    let mut hits: HashMap<usize, Vec<usize>> = inputs.into_iter().map(|id| (id, Vec::new())).collect();
+    while simulator.presses() < MAX_PRESSES && hits.values().any(|h| h.len() < HITS) {
+        simulator.press(&mut [&mut |p: &Pulse| {
+            if p.high && p.to == feeder {
+                let hits = hits.entry(p.from).or_default();
+                if hits.last() != Some(&p.press) {
+                    hits.push(p.press);
+                }
+            }
+        }]);
+    }
+
     let mut periods = Vec::new();
-    let mut count = 0;
-    while periods.len() < 4 {
-        count += 1;
-        let (.., triggered) = input.press_button("hf");
-        if triggered {
-            periods.push(count);
+    for (id, hits) in hits {
+        let input = simulator.name(id).to_owned();
+        if hits.len() < HITS {
+            return Err(CycleError::NoCycle { input, presses: simulator.presses() });
+        }
+        let period = hits[0];
+        if hits.iter().take(HITS).enumerate().any(|(i, &hit)| hit != period * (i + 1)) {
+            return Err(CycleError::Offset { input, hits });
         }
+        periods.push(period as i64);
+    }
+    Ok(math::lcm_vec(periods))
+}
+
+/// The conjunction that is the only module sending pulses to `target`.
+fn find_feeder(input: &Input, target: &str) -> Result<String, CycleError> {
+    let mut feeders: Vec<_> = input.modules
+        .values()
+        .filter(|m| m.next.iter().any(|n| n == target))
+        .map(|m| m.name.clone())
+        .collect();
+    feeders.sort();
+    match feeders.as_slice() {
+        [] => Err(CycleError::NoFeeder),
+        [feeder] if input.modules[feeder].t == ModuleType::Conjunction => Ok(feeder.clone()),
+        [feeder] => Err(CycleError::NotConjunction(feeder.clone())),
+        _ => Err(CycleError::ManyFeeders(feeders)),
     }
-    math::lcm_vec(periods)
 }
 
 #[cfg(test)]
@@ -144,9 +370,120 @@ broadcaster -> a
 %b -> con
 &con -> output";
 
+    /// Two counters resetting every 5 and 7 presses, each sending a high
+    /// pulse through an inverter to `f` when it resets.
+    const COUNTERS: &str = "\
+broadcaster -> b0, d0
+%b0 -> b1, c
+%b1 -> b2
+%b2 -> c
+&c -> b1, b0, i
+&i -> f
+%d0 -> d1, d
+%d1 -> d2, d
+%d2 -> d
+&d -> d0, j
+&j -> f
+&f -> rx";
+
+    #[test]
+    fn input_from_str_errors() {
+        let e = Input::from_str("broadcaster -> a\n% -> b").unwrap_err();
+        assert_eq!((2, 1, "%"), (e.line, e.column, e.found.as_str()));
+        let e = Input::from_str("broadcaster -> a, ").unwrap_err();
+        assert_eq!((1, 19, "a module name"), (e.line, e.column, e.expected.as_str()));
+        let e = Input::from_str("broadcaster -> a\n%a -> b\n&a -> b").unwrap_err();
+        assert_eq!((3, 1, "&a"), (e.line, e.column, e.found.as_str()));
+    }
+
+    #[test]
+    fn input_to_dot() {
+        let dot = Input::from_str(INPUT2).unwrap().to_dot().to_string();
+        assert!(dot.contains("    \"a\" [shape=\"box\", label=\"%a\"];\n    \"a\" -> \"inv\";\n    \"a\" -> \"con\";"));
+        assert!(dot.contains("    \"con\" [shape=\"diamond\", label=\"&con\"];\n    \"con\" -> \"output\";"));
+        assert!(dot.contains("    \"broadcaster\" [shape=\"doublecircle\", label=\"broadcaster\"];"));
+    }
+
     #[test]
     fn test_part1() {
         assert_eq!(32000000, part1(INPUT1.parse().unwrap()));
         assert_eq!(11687500, part1(INPUT2.parse().unwrap()));
     }
+
+    #[test]
+    fn simulator_pulses() {
+        let mut simulator = Simulator::new(&INPUT1.parse().unwrap());
+        let pulses: Vec<_> = simulator
+            .pulses()
+            .into_iter()
+            .map(|p| format!("{} -{}-> {}", simulator.name(p.from), if p.high { "high" } else { "low" }, simulator.name(p.to)))
+            .collect();
+        let expected = [
+            "button -low-> broadcaster",
+            "broadcaster -low-> a",
+            "broadcaster -low-> b",
+            "broadcaster -low-> c",
+            "a -high-> b",
+            "b -high-> c",
+            "c -high-> inv",
+            "inv -low-> a",
+            "a -low-> b",
+            "b -low-> c",
+            "c -low-> inv",
+            "inv -high-> a",
+        ];
+        assert_eq!(expected.to_vec(), pulses);
+        assert!(simulator.pulses().iter().all(|p| p.press == 2));
+    }
+
+    #[test]
+    fn simulator_watchers() {
+        let mut simulator = Simulator::new(&INPUT2.parse().unwrap());
+        let output = simulator.id("output").unwrap();
+        let (mut sent, mut to_output) = (0, Vec::new());
+        for _ in 0..4 {
+            simulator.press(&mut [
+                &mut |_: &Pulse| sent += 1,
+                &mut |p: &Pulse| if p.to == output { to_output.push((p.press, p.high)) },
+            ]);
+        }
+        assert_eq!(17 + 11, sent);
+        assert_eq!(vec![(1, true), (1, false), (2, true), (3, false), (3, true), (4, true)], to_output);
+    }
+
+    #[test]
+    fn simulator_snapshot_and_cycles() {
+        let mut simulator = Simulator::new(&INPUT2.parse().unwrap());
+        simulator.press(&mut []);
+        let snapshot = simulator.snapshot();
+        let pulses = simulator.pulses();
+        simulator.restore(&snapshot);
+        assert_eq!(1, simulator.presses());
+        assert_eq!(pulses, simulator.pulses());
+
+        assert_eq!(Some((0, 4)), Simulator::new(&INPUT2.parse().unwrap()).find_cycle(10));
+        assert_eq!(Some((0, 1)), Simulator::new(&INPUT1.parse().unwrap()).find_cycle(10));
+        assert_eq!(None, Simulator::new(&COUNTERS.parse().unwrap()).find_cycle(10));
+    }
+
+    #[test]
+    fn test_part2() {
+        assert_eq!(Ok(35), part2(COUNTERS.parse().unwrap()));
+    }
+
+    #[test]
+    fn part2_errors() {
+        assert_eq!(Err(CycleError::NoFeeder), part2(INPUT1.parse().unwrap()));
+        let input = format!("{INPUT1}\n&x -> rx\n%y -> rx");
+        let expected = CycleError::ManyFeeders(vec![String::from("x"), String::from("y")]);
+        assert_eq!(Err(expected), part2(input.parse().unwrap()));
+        let input = format!("{INPUT1}\n%y -> rx");
+        assert_eq!(Err(CycleError::NotConjunction(String::from("y"))), part2(input.parse().unwrap()));
+        let input = "broadcaster -> a\n%a -> b\n&f -> rx";
+        assert_eq!(Err(CycleError::NoInputs(String::from("f"))), part2(input.parse().unwrap()));
+        // `a` turns on every other press, starting with the first one.
+        let input = "broadcaster -> a\n%a -> ca\n&ca -> ia\n&ia -> f\n&f -> rx";
+        let expected = CycleError::Offset { input: String::from("ia"), hits: vec![1, 3, 5] };
+        assert_eq!(Err(expected), part2(input.parse().unwrap()));
+    }
 }
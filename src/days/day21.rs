@@ -1,24 +1,21 @@
-use std::{collections::HashSet, str::FromStr, string::ParseError};
+use std::{collections::HashSet, fmt, str::FromStr};
 
 use crate::geometry::*;
+use crate::math::lcm;
+use crate::days::{Checked, Solution};
+use crate::parse::ParseError;
 
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, PartialEq, Eq, Clone)]
 pub struct Input {
-    map: Vec<Vec<char>>,
-    size: (i32, i32),
+    map: Grid<char>,
     start: (i32, i32),
 }
 
 impl Input {
-    fn is_in(&self, pos: (i32, i32)) -> bool {
-        pos.0 >= 0 && pos.0 < self.size.0 &&
-        pos.1 >= 0 && pos.1 < self.size.1
-    }
-
+    /// Tile at `pos` on the map repeated infinitely in every direction.
     fn get(&self, pos: (i32, i32)) -> char {
-        let x = ((pos.0 % self.size.0) + self.size.0) % self.size.0;
-        let y = ((pos.1 % self.size.1) + self.size.1) % self.size.1;
-        self.map[x as usize][y as usize]
+        let size = self.map.size();
+        self.map[(pos.0.rem_euclid(size.0), pos.1.rem_euclid(size.1))]
     }
 }
 
@@ -26,50 +23,117 @@ impl FromStr for Input {
     type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut map = Vec::new();
-        let mut start = (0, 0);
-        for (i, line) in s.lines().enumerate() {
-            let mut v = Vec::new();
-            for (j, c) in line.chars().enumerate() {
-                v.push(c);
-                if c == 'S' {
-                    start = (i as i32, j as i32);
-                }
-            }
-            map.push(v);
-        }
-        let size = (map.len() as i32, map[0].len() as i32);
+        let map = Grid::parse(s, "`.`, `#` or `S`", |c| matches!(c, '.' | '#' | 'S').then_some(c))?;
+        let start = map
+            .find(|&c| c == 'S')
+            .ok_or_else(|| ParseError::new(s, &s[s.len()..], "a start tile `S`"))?;
+
+        Ok(Input {map, start})
+    }
+}
+
+/// Reachable plots could not be extrapolated: their second difference was
+/// still changing after sampling this many map periods.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct UnsettledError(pub usize);
+
+impl fmt::Display for UnsettledError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "reachable plots did not grow quadratically within {} map periods", self.0)
+    }
+}
+
+impl std::error::Error for UnsettledError {}
+
+pub struct Day21;
 
-        Ok(Input {map, size, start})
+impl Solution for Day21 {
+    const DAY: u32 = 21;
+    type Input = Input;
+    type Answer1 = usize;
+    type Answer2 = Checked<usize, UnsettledError>;
+
+    fn parse(input: &str) -> Result<Input, ParseError> {
+        input.parse()
+    }
+
+    fn part1(input: Input) -> usize {
+        part1(input, 64)
+    }
+
+    fn part2(input: Input) -> Checked<usize, UnsettledError> {
+        Checked(part2(input))
     }
 }
 
 pub fn part1(input: Input, steps: usize) -> usize {
-    let mut sets = [HashSet::new(), HashSet::new()];
-    let mut new_pos = HashSet::new();
-    sets[0].insert(input.start);
-    new_pos.insert(input.start);
-    for step in 0..steps {
-        println!("{step}");
-        let mut s = sets[(step+1)%2].clone();
-        let np = new_pos.clone();
-        new_pos.clear();
-        for pos in np {
-            for n in get_neighbors(pos, &input) {
-                if !s.contains(&n) {
-                    new_pos.insert(n);
-                }
-                s.insert(n);
-            }
+    count_plots(&distance_histogram(&input, steps), steps)
+}
+
+pub fn part2(input: Input) -> Result<usize, UnsettledError> {
+    reachable_plots(&input, 26501365)
+}
+
+/// Most map periods `reachable_plots` samples before giving up.
+const MAX_SAMPLES: usize = 16;
+
+/// Plots reachable in exactly `steps` steps on the infinitely tiled map.
+///
+/// The count grows quadratically once `steps` advances by whole map periods,
+/// so it is sampled at `steps % period + k * period` until the second
+/// difference settles and then extrapolated to `k = steps / period`. Gives up
+/// after `MAX_SAMPLES` periods, as every sample walks the whole tiled map.
+fn reachable_plots(input: &Input, steps: usize) -> Result<usize, UnsettledError> {
+    let size = input.map.size();
+    let period = lcm(size.0 as i64, size.1 as i64) as usize;
+    let (rem, target) = (steps % period, steps / period);
+    let mut samples = 8;
+    loop {
+        let k_max = samples.min(target);
+        let histogram = distance_histogram(input, rem + k_max * period);
+        let counts: Vec<_> = (0..=k_max).map(|k| count_plots(&histogram, rem + k * period) as i64).collect();
+        if k_max == target {
+            return Ok(counts[target] as usize);
+        }
+        let second: Vec<_> = counts.windows(3).map(|w| w[2] - 2 * w[1] + w[0]).collect();
+        if let Some(k) = second.windows(3).position(|w| w[0] == w[1] && w[1] == w[2]) {
+            let n = (target - k) as i64;
+            let first = counts[k + 1] - counts[k];
+            return Ok((counts[k] + n * first + n * (n - 1) / 2 * second[k]) as usize);
+        }
+        if samples >= MAX_SAMPLES {
+            return Err(UnsettledError(samples));
         }
-        sets[(step+1)%2] = s;
+        samples *= 2;
     }
-    sets[steps%2].len()
+}
+
+/// `histogram[d]` is the number of plots whose shortest distance from the
+/// start is `d`, for every `d` up to `limit`.
+fn distance_histogram(input: &Input, limit: usize) -> Vec<usize> {
+    let mut histogram = vec![1];
+    let mut seen = HashSet::from([input.start]);
+    let mut frontier = vec![input.start];
+    while histogram.len() <= limit && !frontier.is_empty() {
+        frontier = frontier
+            .into_iter()
+            .flat_map(|pos| get_neighbors(pos, input))
+            .filter(|&n| seen.insert(n))
+            .collect();
+        histogram.push(frontier.len());
+    }
+    histogram
+}
+
+/// Plots reachable in exactly `steps`: those at most `steps` away with the
+/// same parity, since any spare steps can be spent walking back and forth.
+fn count_plots(histogram: &[usize], steps: usize) -> usize {
+    histogram.iter().take(steps + 1).skip(steps % 2).step_by(2).sum()
 }
 
 fn get_neighbors(pos: (i32, i32), input: &Input) -> Vec<(i32, i32)> {
     Direction::iter()
-        .map(|dir| (pos.0 + dir.0, pos.1 + dir.1))
+        .map(|dir| pos + dir)
         .filter(|p| input.get(*p) != '#')
         .collect()
 }
@@ -96,6 +160,51 @@ mod tests {
         assert_eq!(16, part1(Input::from_str(INPUT).unwrap(), 6));
     }
 
+    #[test]
+    fn test_part1_infinite() {
+        let input = Input::from_str(INPUT).unwrap();
+        assert_eq!(50, part1(input.clone(), 10));
+        assert_eq!(1594, part1(input.clone(), 50));
+        assert_eq!(6536, part1(input, 100));
+    }
+
+    #[test]
+    fn test_reachable_plots() {
+        let input = Input::from_str(INPUT).unwrap();
+        assert_eq!(Ok(16), reachable_plots(&input, 6));
+        assert_eq!(Ok(6536), reachable_plots(&input, 100));
+        assert_eq!(Ok(167004), reachable_plots(&input, 500));
+        assert_eq!(Ok(668697), reachable_plots(&input, 1000));
+        assert_eq!(Ok(16733044), reachable_plots(&input, 5000));
+
+        // The serpentine trail crosses the map in many more steps than its
+        // width, so the counts do not settle within the sampled periods.
+        let serpentine = "\
+S..........
+##########.
+...........
+.##########
+...........
+##########.
+...........
+.##########
+...........
+##########.
+...........";
+        let input = Input::from_str(serpentine).unwrap();
+        assert_eq!(Err(UnsettledError(MAX_SAMPLES)), reachable_plots(&input, 26501365));
+    }
+
+    #[test]
+    fn test_get() {
+        let input = Input::from_str(INPUT).unwrap();
+        assert_eq!('S', input.get((5, 5)));
+        assert_eq!('S', input.get((16, -6)));
+        assert_eq!('#', input.get((-10, 5)));
+        assert_eq!('.', input.get((-1, -1)));
+        assert_eq!('#', input.get((-11 * 3 + 1, 11 * 2 + 5)));
+    }
+
     #[test]
     fn test_get_neighbors() {
         let input = Input::from_str(INPUT).unwrap();
@@ -104,6 +213,7 @@ mod tests {
     }
 
     #[test]
+    #[allow(clippy::identity_op)]
     fn test_modulo() {
         assert_eq!(-1, -1 % 10);
         assert_eq!(0, -10 % 10);
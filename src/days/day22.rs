@@ -1,11 +1,15 @@
-use std::{cmp::Ordering, collections::{HashSet, VecDeque}, str::FromStr, string::ParseError};
+use std::{cmp::Ordering, collections::{HashSet, VecDeque}, str::FromStr};
+
+use crate::days::Solution;
+use crate::geometry::Point3;
+use crate::parse::{self, ParseError};
 
 #[derive(Debug, PartialEq, Eq, Clone)]
 struct Brick {
-    bounds: Vec<(i32, i32, i32)>,
+    bounds: [Point3<i32>; 2],
 }
 
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, PartialEq, Eq, Clone)]
 pub struct Input {
     bricks: Vec<Brick>,
     bound: (i32, i32),
@@ -27,14 +31,12 @@ impl FromStr for Brick {
     type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let bounds = s
-            .split('~')
-            .map(|pos| {
-                let coords = pos.split(',').map(|n| n.parse::<i32>().unwrap()).collect::<Vec<_>>();
-                (coords[0], coords[1], coords[2])
-            })
-            .collect();
-        Ok(Brick { bounds })
+        let (from, to) = parse::split_once(s, s, "~")?;
+        let point = |pos: &str| {
+            let [x, y, z] = parse::numbers(s, pos.split(','))?;
+            Ok::<_, ParseError>(Point3(x, y, z))
+        };
+        Ok(Brick { bounds: [point(from)?, point(to)?] })
     }
 }
 
@@ -43,16 +45,36 @@ impl FromStr for Input {
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         let mut bound = (0, 0);
-        let bricks = s.lines().map(|line| {
-            let b = Brick::from_str(line).unwrap();
+        let bricks = parse::lines(s, Brick::from_str)?;
+        for b in &bricks {
             let max_brick = b.bounds[1];
             bound = (bound.0.max(max_brick.0 + 1), bound.1.max(max_brick.1 + 1));
-            b
-        }).collect();
+        }
         Ok(Input { bricks, bound })
     }
 }
 
+pub struct Day22;
+
+impl Solution for Day22 {
+    const DAY: u32 = 22;
+    type Input = Input;
+    type Answer1 = usize;
+    type Answer2 = usize;
+
+    fn parse(input: &str) -> Result<Input, ParseError> {
+        input.parse()
+    }
+
+    fn part1(input: Input) -> usize {
+        part1(input)
+    }
+
+    fn part2(input: Input) -> usize {
+        part2(input)
+    }
+}
+
 pub fn part1(input: Input) -> usize {
     let (above, below) = compute_fall(&input);
     let mut destroyable = 0;
@@ -111,7 +133,7 @@ fn compute_fall(input: &Input) -> (Vec<HashSet<usize>>, Vec<HashSet<usize>>) {
     (above, below)
 }
 
-fn how_many_would_fall(above: &Vec<HashSet<usize>>, below: &Vec<HashSet<usize>>, brick: usize) -> usize {
+fn how_many_would_fall(above: &[HashSet<usize>], below: &[HashSet<usize>], brick: usize) -> usize {
     let mut queue = VecDeque::new();
     let mut hs = HashSet::new();
     queue.push_back(brick);
@@ -148,10 +170,16 @@ mod test {
         assert_eq!(7, part2(Input::from_str(INPUT).unwrap()));
     }
 
+    #[test]
+    fn input_from_str_error() {
+        let e = Input::from_str("1,0,1~1,2,1\n0,0,2~2,0").unwrap_err();
+        assert_eq!((2, 10, "a number"), (e.line, e.column, e.expected.as_str()));
+    }
+
     #[test]
     fn brick_from_str() {
-        assert_eq!(Brick { bounds: vec![(1,0,1), (1,2,1)] }, Brick::from_str("1,0,1~1,2,1").unwrap());
-        assert_eq!(Brick { bounds: vec![(0,0,2), (2,0,2)] }, Brick::from_str("0,0,2~2,0,2").unwrap());
-        assert_eq!(Brick { bounds: vec![(1,1,8), (1,1,9)] }, Brick::from_str("1,1,8~1,1,9").unwrap());
+        assert_eq!(Brick { bounds: [Point3(1,0,1), Point3(1,2,1)] }, Brick::from_str("1,0,1~1,2,1").unwrap());
+        assert_eq!(Brick { bounds: [Point3(0,0,2), Point3(2,0,2)] }, Brick::from_str("0,0,2~2,0,2").unwrap());
+        assert_eq!(Brick { bounds: [Point3(1,1,8), Point3(1,1,9)] }, Brick::from_str("1,1,8~1,1,9").unwrap());
     }
 }
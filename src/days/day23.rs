@@ -1,6 +1,9 @@
-use std::{collections::{HashMap, HashSet, VecDeque}, convert::Infallible, i32, str::FromStr};
+use std::{collections::{HashMap, HashSet, VecDeque}, fmt, panic, str::FromStr, thread};
 
-use crate::geometry::{get_neighbors, Direction, DOWN, LEFT, RIGHT, UP};
+use crate::geometry::{get_neighbors, Direction, Grid, DOWN, LEFT, RIGHT, UP};
+use crate::days::{Checked, Solution};
+use crate::dot::{self, ToDot};
+use crate::parse::ParseError;
 
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
 enum Tile {
@@ -17,31 +20,38 @@ impl Tile {
             Tile::Slope(d) => ignore_slopes || d.reverse() != dir,
         }
     }
+
+    fn symbol(&self) -> char {
+        match *self {
+            Tile::Path => '.',
+            Tile::Tree => '#',
+            Tile::Slope(RIGHT) => '>',
+            Tile::Slope(LEFT) => '<',
+            Tile::Slope(DOWN) => 'v',
+            Tile::Slope(_) => '^',
+        }
+    }
 }
 
-#[derive(Debug, PartialEq, Eq)]
+type Graph = HashMap<(i32, i32), Vec<((i32, i32), i32)>>;
+
+#[derive(Debug, PartialEq, Eq, Clone)]
 pub struct Input {
-    map: Vec<Vec<Tile>>,
-    size: (i32, i32),
+    map: Grid<Tile>,
     start: (i32, i32),
     end: (i32, i32),
 }
 
 impl Input {
-    fn is_in(&self, pos: (i32, i32)) -> bool {
-        pos.0 >= 0 && pos.0 < self.size.0 &&
-        pos.1 >= 0 && pos.1 < self.size.1
-    }
-
     fn get_at(&self, pos: (i32, i32)) -> Tile {
-        self.map[pos.0 as usize][pos.1 as usize]
+        self.map[pos]
     }
 
     fn get_neighbors(&self, pos: (i32, i32), ignore_slopes: bool) -> Vec<(i32, i32)> {
         Direction::iter()
             .filter_map(|d| {
-                let next = (pos.0 + d.0, pos.1 + d.1);
-                if self.is_in(next) && self.get_at(next).can_move(d, ignore_slopes) {
+                let next = pos + d;
+                if self.map.get(next).is_some_and(|t| t.can_move(d, ignore_slopes)) {
                     Some(next)
                 }
                 else {
@@ -52,21 +62,17 @@ impl Input {
     }
 
     fn get_nodes(&self) -> Vec<(i32, i32)> {
-        let mut v = Vec::new();
-        v.push(self.start);
-        for row in 1..self.size.0 - 1 {
-            for col in 1..self.size.1 - 1 {
-                if self.get_at((row, col)) == Tile::Path &&
-                    get_neighbors((row, col)).all(|n| matches!(self.get_at(n), Tile::Slope(_) | Tile::Tree)) {
-                        v.push((row, col));
-                    }
-            }
-        }
+        let mut v = vec![self.start];
+        v.extend(self.map.iter().filter_map(|(pos, &tile)| {
+            let junction = tile == Tile::Path &&
+                get_neighbors(pos).all(|n| matches!(self.map.get(n), Some(Tile::Slope(_) | Tile::Tree)));
+            (junction && pos != self.start && pos != self.end).then_some(pos)
+        }));
         v.push(self.end);
         v
     }
 
-    fn build_graph(&self, ignore_slopes: bool) -> HashMap<(i32, i32), Vec<((i32, i32), i32)>> {
+    fn build_graph(&self, ignore_slopes: bool) -> Graph {
         let mut h = HashMap::new();
         let nodes = self.get_nodes();
         for node in nodes.clone() {
@@ -89,60 +95,298 @@ impl Input {
         }
         h
     }
+
+    /// Tiles on the trail of `distance` steps from junction `from` to junction
+    /// `to`, not counting `from`.
+    fn trail(&self, junctions: &Junctions, from: usize, to: usize, distance: i32, ignore_slopes: bool) -> Option<Vec<(i32, i32)>> {
+        let (from, to) = (junctions.nodes[from], junctions.nodes[to]);
+        self.get_neighbors(from, ignore_slopes).into_iter().find_map(|first| {
+            let (mut previous, mut tiles) = (from, vec![first]);
+            while tiles.len() < distance as usize {
+                let current = *tiles.last()?;
+                if junctions.nodes.contains(&current) {
+                    return None;
+                }
+                let next = self.get_neighbors(current, ignore_slopes).into_iter().find(|&n| n != previous)?;
+                previous = current;
+                tiles.push(next);
+            }
+            (tiles.last() == Some(&to)).then_some(tiles)
+        })
+    }
+
+    fn junctions(&self, ignore_slopes: bool) -> Result<Junctions, HikeError> {
+        Junctions::new(self.get_nodes(), &self.build_graph(ignore_slopes))
+    }
+}
+
+/// The trail graph with junctions numbered by their position in `nodes`, so
+/// a set of them fits in a `u64`. The start is junction 0 and the end is last.
+#[derive(Debug, PartialEq, Eq, Clone)]
+struct Junctions {
+    nodes: Vec<(i32, i32)>,
+    edges: Vec<Vec<(usize, i32)>>,
+    /// Longest trail into each junction, to bound what a hike can still gain.
+    longest_in: Vec<i32>,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum HikeError {
+    /// The visited junctions of a hike are kept in a `u64` mask.
+    TooManyJunctions(usize),
+    NoHike,
+    /// No single trail of the expected length links two junctions of the
+    /// hike, as on maps with open areas instead of one-tile-wide trails.
+    UnwalkableTrail((i32, i32), (i32, i32)),
+}
+
+impl fmt::Display for HikeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HikeError::TooManyJunctions(n) => write!(f, "{n} junctions, but at most 64 are supported"),
+            HikeError::NoHike => write!(f, "no hike reaches the end"),
+            HikeError::UnwalkableTrail(from, to) => write!(f, "no single trail leads from {from:?} to {to:?}"),
+        }
+    }
+}
+
+impl std::error::Error for HikeError {}
+
+/// A hike in progress: where it is, the junctions it went through and how
+/// long it is, plus the most it could still gain from unvisited junctions.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+struct Hike {
+    node: usize,
+    visited: u64,
+    length: i32,
+    remaining: i32,
+}
+
+impl Junctions {
+    fn new(nodes: Vec<(i32, i32)>, graph: &Graph) -> Result<Self, HikeError> {
+        if nodes.len() > 64 {
+            return Err(HikeError::TooManyJunctions(nodes.len()));
+        }
+        let index: HashMap<_, _> = nodes.iter().enumerate().map(|(i, &pos)| (pos, i)).collect();
+        let edges: Vec<Vec<_>> = nodes
+            .iter()
+            .map(|node| graph[node].iter().map(|(next, distance)| (index[next], *distance)).collect())
+            .collect();
+        let mut longest_in = vec![0; nodes.len()];
+        for &(next, distance) in edges.iter().flatten() {
+            longest_in[next] = longest_in[next].max(distance);
+        }
+        Ok(Junctions { nodes, edges, longest_in })
+    }
+
+    fn end(&self) -> usize {
+        self.nodes.len() - 1
+    }
+
+    /// Length of the longest hike from the start to the end that never visits
+    /// a junction twice, with the junctions it goes through. With `parallel`,
+    /// the hike is followed to its first choice of trails and every choice is
+    /// searched on its own thread. Ties go to the earliest choice either way.
+    fn longest_path(&self, parallel: bool) -> Option<(i32, Vec<usize>)> {
+        let start = Hike {
+            node: 0,
+            visited: 1,
+            length: 0,
+            remaining: self.longest_in.iter().sum::<i32>() - self.longest_in[0],
+        };
+        if !parallel {
+            let mut best = None;
+            self.search(start, &mut vec![0], &mut best);
+            return best;
+        }
+        let branches = self.first_branches(start, vec![0]);
+        thread::scope(|scope| {
+            let handles: Vec<_> = branches
+                .into_iter()
+                .map(|(hike, mut route)| {
+                    scope.spawn(move || {
+                        let mut best = None;
+                        self.search(hike, &mut route, &mut best);
+                        best
+                    })
+                })
+                .collect();
+            handles
+                .into_iter()
+                .filter_map(|handle| handle.join().unwrap_or_else(|e| panic::resume_unwind(e)))
+                .reduce(|best, path| if path.0 > best.0 { path } else { best })
+        })
+    }
+
+    /// Searches every hike on from `hike`, which went through the junctions
+    /// in `route`, keeping the longest to reach the end in `best`.
+    fn search(&self, hike: Hike, route: &mut Vec<usize>, best: &mut Option<(i32, Vec<usize>)>) {
+        let longest = best.as_ref().map(|&(length, _)| length);
+        if hike.node == self.end() {
+            if longest.is_none_or(|longest| hike.length > longest) {
+                *best = Some((hike.length, route.clone()));
+            }
+            return;
+        }
+        if longest.is_some_and(|longest| hike.length + hike.remaining <= longest) {
+            return;
+        }
+        for next in self.extend(hike) {
+            route.push(next.node);
+            self.search(next, route, best);
+            route.pop();
+        }
+    }
+
+    fn first_branches(&self, mut hike: Hike, mut route: Vec<usize>) -> Vec<(Hike, Vec<usize>)> {
+        loop {
+            if hike.node == self.end() {
+                return vec![(hike, route)];
+            }
+            let branches: Vec<_> = self.extend(hike).collect();
+            match branches[..] {
+                [next] => {
+                    hike = next;
+                    route.push(next.node);
+                }
+                _ => {
+                    return branches
+                        .into_iter()
+                        .map(|next| (next, [&route[..], &[next.node]].concat()))
+                        .collect();
+                }
+            }
+        }
+    }
+
+    fn extend(&self, hike: Hike) -> impl Iterator<Item = Hike> + '_ {
+        self.edges[hike.node]
+            .iter()
+            .filter(move |&&(next, _)| hike.visited & 1 << next == 0)
+            .map(move |&(next, distance)| Hike {
+                node: next,
+                visited: hike.visited | 1 << next,
+                length: hike.length + distance,
+                remaining: hike.remaining - self.longest_in[next],
+            })
+    }
 }
 
 impl FromStr for Input {
-    type Err = Infallible;
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let map = s
-            .lines()
-            .map(|line| line.chars()
-                .map(|c| match c {
-                    '.' => Tile::Path,
-                    '#' => Tile::Tree,
-                    '>' => Tile::Slope(RIGHT),
-                    '<' => Tile::Slope(LEFT),
-                    'v' => Tile::Slope(DOWN),
-                    '^' => Tile::Slope(UP),
-                    _ => unreachable!(),
-                })
-                .collect::<Vec<_>>())
-            .collect::<Vec<_>>();
-        let size = (map.len() as i32, map[0].len() as i32);
-        let start = (0, map[0].iter().position(|t| *t == Tile::Path).unwrap() as i32);
-        let end = (size.0 - 1, map.last().unwrap().iter().position(|t| *t == Tile::Path).unwrap() as i32);
-        Ok(Input { map, size, start, end })
+        let map = Grid::parse(s, "a path, tree or slope", |c| match c {
+            '.' => Some(Tile::Path),
+            '#' => Some(Tile::Tree),
+            '>' => Some(Tile::Slope(RIGHT)),
+            '<' => Some(Tile::Slope(LEFT)),
+            'v' => Some(Tile::Slope(DOWN)),
+            '^' => Some(Tile::Slope(UP)),
+            _ => None,
+        })?;
+        let find_path = |row: Option<&[Tile]>, line: Option<&str>| {
+            row.and_then(|row| row.iter().position(|t| *t == Tile::Path))
+                .map(|col| col as i32)
+                .ok_or_else(|| ParseError::new(s, line.unwrap_or_default(), "a row with a path tile"))
+        };
+        let start = (0, find_path(map.rows().next(), s.lines().next())?);
+        let end = (map.size().0 - 1, find_path(map.rows().last(), s.lines().last())?);
+        Ok(Input { map, start, end })
+    }
+}
+
+impl ToDot for Input {
+    /// Junctions linked by the trails between them, following slopes, with
+    /// each edge labeled by the trail length.
+    fn to_dot(&self) -> dot::Graph {
+        let name = |pos: (i32, i32)| format!("{},{}", pos.0, pos.1);
+        let mut graph = dot::Graph::directed("day 23");
+        let adjacency = self.build_graph(false);
+        for node in self.get_nodes() {
+            let shape = if node == self.start || node == self.end { "doublecircle" } else { "circle" };
+            graph.node(&name(node), &[("shape", shape)]);
+            for &(next, distance) in &adjacency[&node] {
+                graph.edge(&name(node), &name(next), &[("label", &distance.to_string())]);
+            }
+        }
+        graph
+    }
+}
+
+pub struct Day23;
+
+impl Solution for Day23 {
+    const DAY: u32 = 23;
+    type Input = Input;
+    type Answer1 = Checked<i32, HikeError>;
+    type Answer2 = Checked<i32, HikeError>;
+
+    fn parse(input: &str) -> Result<Input, ParseError> {
+        input.parse()
+    }
+
+    fn part1(input: Input) -> Checked<i32, HikeError> {
+        Checked(part1(input))
+    }
+
+    fn part2(input: Input) -> Checked<i32, HikeError> {
+        Checked(part2(input))
     }
 }
 
-pub fn part1(input: Input) -> i32 {
-    let graph: HashMap<(i32, i32), Vec<((i32, i32), i32)>> = input.build_graph(false);
-    find_longest_path(&graph, input.start, input.end, &mut Vec::new()).unwrap()
+/// The longest hike: the junctions it goes through and every tile it steps
+/// on, both from the start to the end inclusive.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct LongestHike {
+    pub length: i32,
+    pub junctions: Vec<(i32, i32)>,
+    pub tiles: Vec<(i32, i32)>,
 }
 
-pub fn part2(input: Input) -> i32 {
-    let graph = input.build_graph(true);
-    find_longest_path(&graph, input.start, input.end, &mut Vec::new()).unwrap()
+pub fn part1(input: Input) -> Result<i32, HikeError> {
+    longest_length(&input, false, false)
 }
 
-fn find_longest_path(
-    adj_list: &HashMap<(i32, i32), Vec<((i32, i32), i32)>>,
-    curr: (i32, i32),
-    end: (i32, i32),
-    visited: &mut Vec<(i32, i32)>
-) -> Option<i32> {
-    if curr == end { return Some(0); }
-    if visited.contains(&curr) { return None; }
-    visited.push(curr);
-    let mut max = None;
-    for &(pos, dist) in &adj_list[&curr] {
-        if let Some(path_len) = find_longest_path(adj_list, pos, end, visited) {
-            max = Some(max.unwrap_or(0).max(path_len + dist));
-        }
+pub fn part2(input: Input) -> Result<i32, HikeError> {
+    longest_length(&input, true, true)
+}
+
+fn longest_length(input: &Input, ignore_slopes: bool, parallel: bool) -> Result<i32, HikeError> {
+    let junctions = input.junctions(ignore_slopes)?;
+    let (length, _) = junctions.longest_path(parallel).ok_or(HikeError::NoHike)?;
+    Ok(length)
+}
+
+pub fn longest_hike(input: &Input, ignore_slopes: bool, parallel: bool) -> Result<LongestHike, HikeError> {
+    let junctions = input.junctions(ignore_slopes)?;
+    let (length, route) = junctions.longest_path(parallel).ok_or(HikeError::NoHike)?;
+    let mut tiles = vec![input.start];
+    for pair in route.windows(2) {
+        let (from, to) = (pair[0], pair[1]);
+        let unwalkable = HikeError::UnwalkableTrail(junctions.nodes[from], junctions.nodes[to]);
+        let trail = junctions.edges[from]
+            .iter()
+            .filter(|&&(next, _)| next == to)
+            .map(|&(_, distance)| distance)
+            .max()
+            .and_then(|distance| input.trail(&junctions, from, to, distance, ignore_slopes))
+            .ok_or(unwalkable)?;
+        tiles.extend(trail);
+    }
+    let junctions = route.into_iter().map(|node| junctions.nodes[node]).collect();
+    Ok(LongestHike { length, junctions, tiles })
+}
+
+/// The map with the hike drawn over it as in the puzzle text: `S` at the
+/// start and `O` on every step after.
+pub fn render(input: &Input, hike: &LongestHike) -> String {
+    let mut map = input.map.map(|tile| tile.symbol());
+    for &pos in &hike.tiles {
+        map[pos] = 'O';
     }
-    visited.pop();
-    max
+    map[input.start] = 'S';
+    map.to_string()
 }
 
 #[cfg(test)]
@@ -174,10 +418,12 @@ mod tests {
 #.....###...###...#...#
 #####################.#";
 
+    const OPEN_AREA: &str = "#.###\n#...#\n#...#\n###.#";
+
     #[test]
     fn input_from_str() {
         let input = Input::from_str(INPUT).unwrap();
-        assert_eq!((23, 23), input.size);
+        assert_eq!((23, 23), input.map.size());
         assert_eq!((0, 1), input.start);
         assert_eq!((22, 21), input.end);
         assert_eq!(Tile::Path, input.get_at((5, 16)));
@@ -194,20 +440,123 @@ mod tests {
 
     #[test]
     fn tile_can_move() {
-        assert_eq!(true, Tile::Path.can_move(RIGHT, false));
-        assert_eq!(false, Tile::Tree.can_move(RIGHT, false));
-        assert_eq!(true, Tile::Slope(RIGHT).can_move(RIGHT, false));
-        assert_eq!(false, Tile::Slope(RIGHT).can_move(LEFT, false));
-        assert_eq!(true, Tile::Slope(RIGHT).can_move(LEFT, true));
+        assert!(Tile::Path.can_move(RIGHT, false));
+        assert!(!Tile::Tree.can_move(RIGHT, false));
+        assert!(Tile::Slope(RIGHT).can_move(RIGHT, false));
+        assert!(!Tile::Slope(RIGHT).can_move(LEFT, false));
+        assert!(Tile::Slope(RIGHT).can_move(LEFT, true));
+    }
+
+    #[test]
+    fn test_junctions() {
+        let input = Input::from_str(INPUT).unwrap();
+        let junctions = input.junctions(false).unwrap();
+        assert_eq!(9, junctions.nodes.len());
+        assert_eq!(input.start, junctions.nodes[0]);
+        assert_eq!(input.end, junctions.nodes[junctions.end()]);
+        assert_eq!((5, 3), junctions.nodes[2]);
+        assert_eq!(vec![(2, 15)], junctions.edges[0]);
+        assert_eq!(15, junctions.longest_in[2]);
+    }
+
+    #[test]
+    fn test_longest_path() {
+        let input = Input::from_str(INPUT).unwrap();
+        for (ignore_slopes, expected) in [(false, 94), (true, 154)] {
+            let junctions = input.junctions(ignore_slopes).unwrap();
+            let (length, route) = junctions.longest_path(false).unwrap();
+            assert_eq!(expected, length);
+            assert_eq!(Some((length, route)), junctions.longest_path(true));
+        }
+        let junctions = Junctions::new(vec![(0, 0), (1, 1)], &HashMap::from([((0, 0), vec![]), ((1, 1), vec![])])).unwrap();
+        assert_eq!(None, junctions.longest_path(false));
+        assert_eq!(None, junctions.longest_path(true));
+
+        // Both branches from the start are as long; the first one is kept.
+        let graph = HashMap::from([
+            ((0, 0), vec![((1, 0), 1), ((2, 0), 1)]),
+            ((1, 0), vec![((3, 0), 1)]),
+            ((2, 0), vec![((3, 0), 1)]),
+            ((3, 0), vec![]),
+        ]);
+        let junctions = Junctions::new(vec![(0, 0), (1, 0), (2, 0), (3, 0)], &graph).unwrap();
+        assert_eq!(Some((2, vec![0, 1, 3])), junctions.longest_path(false));
+        assert_eq!(Some((2, vec![0, 1, 3])), junctions.longest_path(true));
+
+        let nodes: Vec<_> = (0..65).map(|i| (i, 0)).collect();
+        let graph = nodes.iter().map(|&node| (node, vec![])).collect();
+        assert_eq!(Err(HikeError::TooManyJunctions(65)), Junctions::new(nodes, &graph));
+    }
+
+    #[test]
+    fn test_longest_hike() {
+        let input = Input::from_str(INPUT).unwrap();
+        let hike = longest_hike(&input, false, false).unwrap();
+        assert_eq!(94, hike.length);
+        assert_eq!(95, hike.tiles.len());
+        assert_eq!(vec![(0, 1), (5, 3), (3, 11), (13, 13), (11, 21), (19, 19), (22, 21)], hike.junctions);
+        assert!(hike.tiles.windows(2).all(|w| (w[0].0 - w[1].0).abs() + (w[0].1 - w[1].1).abs() == 1));
+        assert_eq!(hike.tiles.len(), hike.tiles.iter().collect::<HashSet<_>>().len());
+
+        let hike = longest_hike(&input, true, true).unwrap();
+        assert_eq!(154, hike.length);
+        assert_eq!(155, hike.tiles.len());
+        assert_eq!(Some(&input.end), hike.tiles.last());
+
+        let input = Input::from_str("#.###\n#####\n###.#").unwrap();
+        assert_eq!(Err(HikeError::NoHike), longest_hike(&input, true, false));
+
+        let input = Input::from_str(OPEN_AREA).unwrap();
+        assert_eq!(Err(HikeError::UnwalkableTrail((0, 1), (3, 3))), longest_hike(&input, false, false));
+    }
+
+    #[test]
+    fn test_render() {
+        let input = Input::from_str(INPUT).unwrap();
+        let hike = longest_hike(&input, false, false).unwrap();
+        let expected = "\
+#S#####################
+#OOOOOOO#########...###
+#######O#########.#.###
+###OOOOO#OOO>.###.#.###
+###O#####O#O#.###.#.###
+###OOOOO#O#O#.....#...#
+###v###O#O#O#########.#
+###...#O#O#OOOOOOO#...#
+#####.#O#O#######O#.###
+#.....#O#O#OOOOOOO#...#
+#.#####O#O#O#########v#
+#.#...#OOO#OOO###OOOOO#
+#.#.#v#######O###O###O#
+#...#.>.#...>OOO#O###O#
+#####v#.#.###v#O#O###O#
+#.....#...#...#O#O#OOO#
+#.#########.###O#O#O###
+#...###...#...#OOO#O###
+###.###.#.###v#####O###
+#...#...#.#.>.>.#.>O###
+#.###.###.#.###.#.#O###
+#.....###...###...#OOO#
+#####################O#";
+        assert_eq!(expected, render(&input, &hike));
+    }
+
+    #[test]
+    fn input_to_dot() {
+        let dot = Input::from_str(INPUT).unwrap().to_dot().to_string();
+        assert!(dot.contains("    \"0,1\" [shape=\"doublecircle\"];\n    \"0,1\" -> \"5,3\" [label=\"15\"];"));
+        assert!(dot.contains("    \"22,21\" [shape=\"doublecircle\"];"));
     }
 
     #[test]
     fn test_part1() {
-        assert_eq!(94, part1(Input::from_str(INPUT).unwrap()));
+        assert_eq!(Ok(94), part1(Input::from_str(INPUT).unwrap()));
+        assert_eq!(Ok(5), part1(Input::from_str(OPEN_AREA).unwrap()));
     }
 
     #[test]
     fn test_part2() {
-        assert_eq!(154, part2(Input::from_str(INPUT).unwrap()));
+        assert_eq!(Ok(154), part2(Input::from_str(INPUT).unwrap()));
+        assert_eq!(Ok(5), part2(Input::from_str(OPEN_AREA).unwrap()));
     }
 }
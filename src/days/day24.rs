@@ -1,9 +1,14 @@
-use std::{convert::Infallible, str::FromStr};
+use std::{fmt, str::FromStr};
+
+use crate::days::{Checked, Solution};
+use crate::geometry::{Point, Point3};
+use crate::math::gcd;
+use crate::parse::{self, ParseError};
 
 #[derive(Debug, PartialEq, Eq, Clone)]
 struct Hailstone {
-    pos: (i64, i64, i64),
-    vel: (i64, i64, i64),
+    pos: Point3<i64>,
+    vel: Point3<i64>,
 }
 
 #[derive(Debug, PartialEq, Eq, Clone)]
@@ -12,53 +17,78 @@ pub struct Input {
 }
 
 impl FromStr for Hailstone {
-    type Err = Infallible;
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut parts = s
-            .split("@")
-            .map(|part| part
-                .split(",")
-                .map(|c| c.trim().parse::<i64>().unwrap())
-                .collect::<Vec<_>>());
-        let pos = {
-            let p = parts.next().unwrap();
-            (p[0], p[1], p[2])
-        };
-        let vel = {
-            let v = parts.next().unwrap();
-            (v[0], v[1], v[2])
-        };
-        Ok(Hailstone { pos, vel })
+        let (pos, vel) = parse::split_once(s, s, "@")?;
+        let [x, y, z] = parse::numbers(s, pos.split(","))?;
+        let [vx, vy, vz] = parse::numbers(s, vel.split(","))?;
+        Ok(Hailstone { pos: Point3(x, y, z), vel: Point3(vx, vy, vz) })
     }
 }
 
 impl FromStr for Input {
-    type Err = Infallible;
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let hailstones = s
-            .lines()
-            .map(|line| Hailstone::from_str(line).unwrap())
-            .collect();
+        let hailstones = parse::lines(s, Hailstone::from_str)?;
         Ok(Input { hailstones })
     }
 }
 
+/// No single rock throw hits every hailstone.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct ThrowError;
+
+impl fmt::Display for ThrowError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "no rock hits every hailstone")
+    }
+}
+
+impl std::error::Error for ThrowError {}
+
+pub struct Day24;
+
+impl Solution for Day24 {
+    const DAY: u32 = 24;
+    type Input = Input;
+    type Answer1 = i32;
+    type Answer2 = Checked<i64, ThrowError>;
+
+    fn parse(input: &str) -> Result<Input, ParseError> {
+        input.parse()
+    }
+
+    fn part1(input: Input) -> i32 {
+        part1(input)
+    }
+
+    fn part2(input: Input) -> Checked<i64, ThrowError> {
+        Checked(part2(input))
+    }
+}
+
 pub fn part1(input: Input) -> i32 {
-    const MIN: f32 = 200000000000000_f32;
-    const MAX: f32 = 400000000000000_f32;
+    const MIN: i64 = 200000000000000;
+    const MAX: i64 = 400000000000000;
     count_intersections(input, MIN, MAX)
 }
 
-fn count_intersections(input: Input, min: f32, max: f32) -> i32 {
+pub fn part2(input: Input) -> Result<i64, ThrowError> {
+    let rock = throw_rock(&input.hailstones).ok_or(ThrowError)?;
+    Ok(rock.pos.0 + rock.pos.1 + rock.pos.2)
+}
+
+fn count_intersections(input: Input, min: i64, max: i64) -> i32 {
+    let (min, max) = (min as i128, max as i128);
     let mut count = 0;
-    for i in 0..input.hailstones.len() - 1 {
-        for j in i+1..input.hailstones.len() {
-            match intersect_2d(&input.hailstones[i], &input.hailstones[j]) {
-                Some((x, y))
-                if x > min && x < max
-                && y > min && y < max => count += 1,
+    for (i, a) in input.hailstones.iter().enumerate() {
+        for b in &input.hailstones[i + 1..] {
+            match intersect_2d(a, b) {
+                Some((Point(x, y), d))
+                if x >= min * d && x <= max * d
+                && y >= min * d && y <= max * d => count += 1,
                 _ => ()
             }
         }
@@ -66,30 +96,89 @@ fn count_intersections(input: Input, min: f32, max: f32) -> i32 {
     count
 }
 
-fn intersect_2d(h1: &Hailstone, h2: &Hailstone) -> Option<(f32,f32)> {
-    let (x1, y1, _) = h1.pos;
-    let (vx1, vy1, _) = h1.vel;
-    let (x2, y2, _) = h2.pos;
-    let (vx2, vy2, _) = h2.vel;
+/// Where the paths of two hailstones cross in the future, ignoring the z
+/// axis, as numerators over a positive common denominator.
+fn intersect_2d(h1: &Hailstone, h2: &Hailstone) -> Option<(Point<i128>, i128)> {
+    let Point(x1, y1) = h1.pos.xy().map(i128::from);
+    let Point(vx1, vy1) = h1.vel.xy().map(i128::from);
+    let Point(vx2, vy2) = h2.vel.xy().map(i128::from);
+    let Point(dx, dy) = (h2.pos.xy() - h1.pos.xy()).map(i128::from);
 
-    let denominator = vx1 * vy2 - vy1 * vx2;
+    let mut denominator = vx1 * vy2 - vy1 * vx2;
 
     if denominator == 0 {
         return None;
     }
 
-    let t = ((x2 - x1) * vy2 - (y2 - y1) * vx2) as f32 / denominator as f32;
-    let s = ((x2 - x1) * vy1 - (y2 - y1) * vx1) as f32 / denominator as f32;
+    let mut t = dx * vy2 - dy * vx2;
+    let mut s = dx * vy1 - dy * vx1;
+    if denominator < 0 {
+        (t, s, denominator) = (-t, -s, -denominator);
+    }
 
-    if t < 0.0 || s < 0.0 {
+    if t < 0 || s < 0 {
         return None;
     }
 
-    // Calculate intersection point using t or s
-    let intersection_x = x1 as f32 + t * vx1 as f32;
-    let intersection_y = y1 as f32 + t * vy1 as f32;
+    Some((Point(x1 * denominator + t * vx1, y1 * denominator + t * vy1), denominator))
+}
 
-    Some((intersection_x, intersection_y))
+/// Finds the rock throw that hits every hailstone.
+///
+/// Seen from the first hailstone, the rock passes through the origin, so its
+/// relative velocity lies in the plane spanned by the origin and any other
+/// hailstone's path. Two such planes give the direction, which then gives
+/// the times the rock hits those two hailstones. Everything stays in `i128`
+/// because the direction is reduced by its gcd before it is used.
+fn throw_rock(hailstones: &[Hailstone]) -> Option<Hailstone> {
+    let wide = |h: &Hailstone| (h.pos.map(i128::from), h.vel.map(i128::from));
+    let (p0, v0) = wide(hailstones.first()?);
+    let relative: Vec<_> = hailstones[1..]
+        .iter()
+        .map(|h| {
+            let (p, v) = wide(h);
+            (p - p0, v - v0)
+        })
+        .collect();
+    let pairs = (0..relative.len()).flat_map(|i| (i + 1..relative.len()).map(move |j| (i, j)));
+    pairs.into_iter().find_map(|(i, j)| {
+        let ((p1, v1), (p2, v2)) = (relative[i], relative[j]);
+        let direction = p1.cross(v1).cross(p2.cross(v2));
+        let g = gcd(gcd(direction.0, direction.1), direction.2);
+        if g == 0 {
+            return None;
+        }
+        let direction = direction.map(|c| c / g);
+        let (t1, t2) = (hit_time(p1, v1, direction)?, hit_time(p2, v2, direction)?);
+        if t1 == t2 {
+            return None;
+        }
+        let ((q1, w1), (q2, w2)) = (wide(&hailstones[i + 1]), wide(&hailstones[j + 1]));
+        let (hit1, hit2) = (q1 + w1 * t1, q2 + w2 * t2);
+        let diff = hit2 - hit1;
+        let dt = t2 - t1;
+        if diff.0 % dt != 0 || diff.1 % dt != 0 || diff.2 % dt != 0 {
+            return None;
+        }
+        let vel = diff.map(|c| c / dt);
+        let pos = hit1 - vel * t1;
+        let hits_all = hailstones.iter().all(|h| {
+            let (p, v) = wide(h);
+            (pos - p).cross(vel - v) == Point3(0, 0, 0)
+        });
+        let narrow = |p: Point3<i128>| Some(Point3(p.0.try_into().ok()?, p.1.try_into().ok()?, p.2.try_into().ok()?));
+        hits_all.then(|| Some(Hailstone { pos: narrow(pos)?, vel: narrow(vel)? })).flatten()
+    })
+}
+
+/// Integer time at which the path `p + v * t` crosses the line through the
+/// origin along `direction`, if there is one.
+fn hit_time(p: Point3<i128>, v: Point3<i128>, direction: Point3<i128>) -> Option<i128> {
+    let (a, b) = (p.cross(direction), v.cross(direction));
+    let (num, den) = [(a.0, b.0), (a.1, b.1), (a.2, b.2)]
+        .into_iter()
+        .max_by_key(|(_, den)| den.abs())?;
+    (den != 0 && num % den == 0).then(|| -num / den)
 }
 
 #[cfg(test)]
@@ -105,12 +194,53 @@ mod tests {
 
     #[test]
     fn hailstone_from_str() {
-        let h = Hailstone { pos: (19, 13, 30), vel: (-2,  1, -2) };
+        let h = Hailstone { pos: Point3(19, 13, 30), vel: Point3(-2,  1, -2) };
         assert_eq!(h, Hailstone::from_str("19, 13, 30 @ -2,  1, -2").unwrap());
+        let e = Hailstone::from_str("19, 13 @ -2,  1, -2").unwrap_err();
+        assert_eq!((8, "a number"), (e.column, e.expected.as_str()));
     }
 
     #[test]
     fn test_part1() {
-        assert_eq!(2, count_intersections(Input::from_str(INPUT).unwrap(), 7.0, 27.0));
+        assert_eq!(2, count_intersections(Input::from_str(INPUT).unwrap(), 7, 27));
+    }
+
+    #[test]
+    fn test_part2() {
+        assert_eq!(Ok(47), part2(Input::from_str(INPUT).unwrap()));
+        let rock = throw_rock(&Input::from_str(INPUT).unwrap().hailstones).unwrap();
+        assert_eq!(Hailstone { pos: Point3(24, 13, 10), vel: Point3(-3, 1, 2) }, rock);
+        assert_eq!(Err(ThrowError), part2(Input { hailstones: vec![] }));
+    }
+
+    #[test]
+    fn test_intersect_2d() {
+        let input = Input::from_str(INPUT).unwrap();
+        let h = &input.hailstones;
+        let (Point(x, y), d) = intersect_2d(&h[0], &h[1]).unwrap();
+        assert_eq!((43 * d, 46 * d), (x * 3, y * 3));
+        assert_eq!(None, intersect_2d(&h[1], &h[2]));
+        assert_eq!(None, intersect_2d(&h[0], &h[4]));
+    }
+
+    #[test]
+    fn intersect_2d_is_exact() {
+        // The paths cross at x = 4e14 - 1/3, which f32 rounds onto the bound.
+        let a = Hailstone { pos: Point3(400000000000000, 0, 0), vel: Point3(-1, 3, 0) };
+        let b = Hailstone { pos: Point3(399999999999999, 1, 0), vel: Point3(1, 0, 0) };
+        let input = Input { hailstones: vec![a, b] };
+        assert_eq!(1, count_intersections(input, 0, 400000000000000));
+    }
+
+    #[test]
+    fn test_area_is_inclusive() {
+        // The paths cross at (7, 7), right on the corner of the test area.
+        let a = Hailstone { pos: Point3(0, 7, 0), vel: Point3(1, 0, 0) };
+        let b = Hailstone { pos: Point3(7, 0, 0), vel: Point3(0, 1, 0) };
+        let input = Input { hailstones: vec![a, b] };
+        assert_eq!(1, count_intersections(input.clone(), 7, 27));
+        assert_eq!(1, count_intersections(input.clone(), 0, 7));
+        assert_eq!(0, count_intersections(input, 8, 27));
+        assert_eq!(0, count_intersections(Input { hailstones: vec![] }, 7, 27));
     }
 }
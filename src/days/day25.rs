@@ -0,0 +1,196 @@
+use std::{collections::{HashMap, VecDeque}, fmt, str::FromStr};
+
+use crate::days::{Checked, Solution, Unsolved};
+use crate::parse::{self, ParseError};
+
+/// Undirected wiring graph, with components numbered in order of appearance.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Input {
+    adjacency: Vec<Vec<usize>>,
+}
+
+/// Unit-capacity flow network over the wiring graph: every wire becomes two
+/// opposite arcs that are each other's residual.
+struct Network {
+    arcs: Vec<(usize, usize)>,
+    outgoing: Vec<Vec<usize>>,
+    capacity: Vec<i32>,
+}
+
+impl Network {
+    fn new(input: &Input) -> Self {
+        let mut arcs = Vec::new();
+        let mut outgoing = vec![Vec::new(); input.adjacency.len()];
+        for (from, neighbors) in input.adjacency.iter().enumerate() {
+            for &to in neighbors.iter().filter(|&&to| from < to) {
+                outgoing[from].push(arcs.len());
+                arcs.push((from, to));
+                outgoing[to].push(arcs.len());
+                arcs.push((to, from));
+            }
+        }
+        let capacity = vec![1; arcs.len()];
+        Network { arcs, outgoing, capacity }
+    }
+
+    /// Breadth-first search over arcs with capacity left, returning the arc
+    /// used to reach every visited node other than `source`.
+    fn search(&self, source: usize) -> Vec<Option<usize>> {
+        let mut via = vec![None; self.outgoing.len()];
+        let mut visited = vec![false; self.outgoing.len()];
+        let mut queue = VecDeque::from([source]);
+        visited[source] = true;
+        while let Some(node) = queue.pop_front() {
+            for &arc in &self.outgoing[node] {
+                let to = self.arcs[arc].1;
+                if self.capacity[arc] > 0 && !visited[to] {
+                    visited[to] = true;
+                    via[to] = Some(arc);
+                    queue.push_back(to);
+                }
+            }
+        }
+        via
+    }
+
+    /// Pushes one unit of flow from `source` to `sink`, if there is a path.
+    fn augment(&mut self, source: usize, sink: usize) -> bool {
+        let via = self.search(source);
+        if via[sink].is_none() {
+            return false;
+        }
+        let mut node = sink;
+        while node != source {
+            let arc = via[node].unwrap();
+            self.capacity[arc] -= 1;
+            self.capacity[arc ^ 1] += 1;
+            node = self.arcs[arc].0;
+        }
+        true
+    }
+}
+
+impl FromStr for Input {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut ids = HashMap::new();
+        let mut adjacency: Vec<Vec<usize>> = Vec::new();
+        let mut id = |name: &str, adjacency: &mut Vec<Vec<usize>>| {
+            *ids.entry(name.to_owned()).or_insert_with(|| {
+                adjacency.push(Vec::new());
+                adjacency.len() - 1
+            })
+        };
+        let wires = parse::lines(s, |line| {
+            let (from, to) = parse::split_once(line, line, ": ")?;
+            if let Some(name) = to.split(' ').chain([from]).find(|name| name.is_empty()) {
+                return Err(ParseError::new(line, name, "a component name"));
+            }
+            Ok((from.to_owned(), to.split(' ').map(String::from).collect::<Vec<_>>()))
+        })?;
+        for (from, to) in wires {
+            let from = id(&from, &mut adjacency);
+            for name in to {
+                let to = id(&name, &mut adjacency);
+                adjacency[from].push(to);
+                adjacency[to].push(from);
+            }
+        }
+        Ok(Input { adjacency })
+    }
+}
+
+/// No cut of this many wires splits the graph in two.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct CutError(pub usize);
+
+impl fmt::Display for CutError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "no cut of {} wires splits the graph", self.0)
+    }
+}
+
+impl std::error::Error for CutError {}
+
+pub struct Day25;
+
+impl Solution for Day25 {
+    const DAY: u32 = 25;
+    type Input = Input;
+    type Answer1 = Checked<usize, CutError>;
+    type Answer2 = Unsolved;
+
+    fn parse(input: &str) -> Result<Input, ParseError> {
+        input.parse()
+    }
+
+    fn part1(input: Input) -> Checked<usize, CutError> {
+        Checked(part1(input))
+    }
+
+    fn part2(_input: Input) -> Unsolved {
+        Unsolved
+    }
+}
+
+pub fn part1(input: Input) -> Result<usize, CutError> {
+    let size = find_cut(&input, 3).ok_or(CutError(3))?;
+    Ok(size * (input.adjacency.len() - size))
+}
+
+/// Size of the component containing the first node after cutting exactly
+/// `wires` wires, found by looking for a sink whose max flow from the first
+/// node is `wires`; the residual graph then only reaches the source's side.
+fn find_cut(input: &Input, wires: usize) -> Option<usize> {
+    let source = 0;
+    (1..input.adjacency.len()).find_map(|sink| {
+        let mut network = Network::new(input);
+        let mut flow = 0;
+        while flow <= wires && network.augment(source, sink) {
+            flow += 1;
+        }
+        (flow == wires).then(|| 1 + network.search(source).iter().filter(|via| via.is_some()).count())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INPUT: &str = "\
+jqt: rhn xhk nvd
+rsh: frs pzl lsr
+xhk: hfx
+cmg: qnr nvd lhk bvb
+rhn: xhk bvb hfx
+bvb: xhk hfx
+pzl: lsr hfx nvd
+qnr: nvd
+ntq: jqt hfx bvb xhk
+nvd: lhk
+lsr: lhk
+rzs: qnr cmg lsr rsh
+frs: qnr lhk lsr";
+
+    #[test]
+    fn test_part1() {
+        assert_eq!(Ok(54), part1(Input::from_str(INPUT).unwrap()));
+        assert_eq!(Err(CutError(3)), part1(Input::from_str("a: b").unwrap()));
+    }
+
+    #[test]
+    fn input_from_str() {
+        let input = Input::from_str("a: b c\nb: c").unwrap();
+        assert_eq!(vec![vec![1, 2], vec![0, 2], vec![0, 1]], input.adjacency);
+        let e = Input::from_str("a: b\nb c").unwrap_err();
+        assert_eq!((2, 1, "`: `"), (e.line, e.column, e.expected.as_str()));
+    }
+
+    #[test]
+    fn test_find_cut() {
+        let input = Input::from_str(INPUT).unwrap();
+        assert_eq!(None, find_cut(&input, 2));
+        assert!(matches!(find_cut(&input, 3), Some(6 | 9)));
+    }
+}
@@ -1,44 +1,62 @@
-use std::{convert::Infallible, str::FromStr};
+use std::str::FromStr;
 
-#[derive(Debug, PartialEq, Eq)]
+use crate::days::Solution;
+use crate::geometry::get_all_neighbors;
+use crate::parse::ParseError;
+
+#[derive(Debug, PartialEq, Eq, Clone)]
 struct Number {
     n: i32,
     pos: [(i32, i32); 2],
 }
 
 impl Number {
+    fn cells(&self) -> impl Iterator<Item = (i32,i32)> {
+        let row = self.pos[0].0;
+        (self.pos[0].1..=self.pos[1].1).map(move |col| (row, col))
+    }
+
     fn around(&self) -> impl Iterator<Item = (i32,i32)> {
-        let (x0, y0) = (self.pos[0].0 - 1, self.pos[0].1 - 1);
-        let (x1, y1) = (self.pos[1].0 + 1, self.pos[1].1 + 1);
-        (x0..=x1).flat_map(move |i| (y0..=y1).map(move |j| (i, j)))
+        self.cells().flat_map(get_all_neighbors)
     }
 }
 
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, PartialEq, Eq, Clone)]
 struct Symbol {
     c: char,
     pos: (i32, i32),
 }
 
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, PartialEq, Eq, Clone)]
 pub struct Input {
     numbers: Vec<Number>,
     symbols: Vec<Symbol>,
 }
 
 impl FromStr for Input {
-    type Err = Infallible;
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         let mut numbers = Vec::new();
         let mut symbols = Vec::new();
         let mut n: i32 = 0;
         let mut digits: usize = 0;
+        let mut start = 0;
 
         for (i, line) in s.lines().enumerate() {
-            for (j, c) in line.chars().enumerate() {
-                if c.is_digit(10) {
-                    n = n * 10 + c.to_digit(10).unwrap() as i32;
+            for (j, (offset, c)) in line.char_indices().enumerate() {
+                if c.is_ascii_digit() {
+                    if digits == 0 {
+                        start = offset;
+                    }
+                    n = n
+                        .checked_mul(10)
+                        .and_then(|n| n.checked_add(c.to_digit(10).unwrap() as i32))
+                        .ok_or_else(|| {
+                            let run = &line[start..];
+                            let end = run.find(|c: char| !c.is_ascii_digit()).unwrap_or(run.len());
+                            ParseError::new(s, &run[..end], "a number")
+                        })?;
                     digits += 1;
                 } else {
                     if c != '.' {
@@ -61,13 +79,33 @@ impl FromStr for Input {
     }
 }
 
+pub struct Day3;
+
+impl Solution for Day3 {
+    const DAY: u32 = 3;
+    type Input = Input;
+    type Answer1 = i32;
+    type Answer2 = i32;
+
+    fn parse(input: &str) -> Result<Input, ParseError> {
+        input.parse()
+    }
+
+    fn part1(input: Input) -> i32 {
+        part1(input)
+    }
+
+    fn part2(input: Input) -> i32 {
+        part2(input)
+    }
+}
+
 pub fn part1(input: Input) -> i32 {
     input.numbers
         .into_iter()
         .filter(|n| n
             .around()
-            .into_iter()
-            .any(|p| input.symbols.iter().find(|&sym| sym.pos == p).is_some()))
+            .any(|p| input.symbols.iter().any(|sym| sym.pos == p)))
         .map(|n| n.n)
         .sum()
 }
@@ -104,6 +142,13 @@ mod tests {
 ...$.*....
 .664.598..";
 
+    #[test]
+    fn input_from_str_errors() {
+        let e = Input::from_str("..*..\n.1234567890123*").unwrap_err();
+        assert_eq!((2, 2, "1234567890123"), (e.line, e.column, e.found.as_str()));
+        assert_eq!("a number", e.expected);
+    }
+
     #[test]
     fn test_part1() {
         assert_eq!(4361, part1(Input::from_str(INPUT).unwrap()));
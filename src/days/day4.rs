@@ -1,42 +1,65 @@
-use std::{collections::HashSet, convert::Infallible, str::FromStr};
+use std::{collections::HashSet, str::FromStr};
 
-#[derive(Debug, PartialEq, Eq)]
+use crate::days::Solution;
+use crate::parse::{self, ParseError};
+
+#[derive(Debug, PartialEq, Eq, Clone)]
 struct Card {
     id: i32,
     score: u32,
 }
 
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, PartialEq, Eq, Clone)]
 pub struct Input {
     cards: Vec<Card>,
 }
 
 impl FromStr for Card {
-    type Err = Infallible;
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let (id, rest) = s.split_once(':').unwrap();
-        let id = id.split_whitespace().last().unwrap().parse::<i32>().unwrap();
-        let v = rest
-            .split('|')
-            .map(|group| group
-                .split_whitespace()
-                .map(|n| n.parse::<i32>().unwrap())
-                .collect::<HashSet<_>>())
-            .collect::<Vec<_>>();
-        Ok(Card { id, score: v[0].intersection(&v[1]).count() as u32})
+        let (id, rest) = parse::split_once(s, s, ":")?;
+        let id = parse::number(s, id.split_whitespace().last().unwrap_or(id))?;
+        let (winning, owned) = parse::split_once(s, rest, "|")?;
+        let numbers = |group: &str| group
+            .split_whitespace()
+            .map(|n| parse::number::<i32>(s, n))
+            .collect::<Result<HashSet<_>, _>>();
+        let (winning, owned) = (numbers(winning)?, numbers(owned)?);
+        Ok(Card { id, score: winning.intersection(&owned).count() as u32})
     }
 }
 
 impl FromStr for Input {
-    type Err = Infallible;
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let cards = s.lines().map(|line| Card::from_str(line).unwrap()).collect();
+        let cards = parse::lines(s, Card::from_str)?;
         Ok(Input { cards })
     }
 }
 
+pub struct Day4;
+
+impl Solution for Day4 {
+    const DAY: u32 = 4;
+    type Input = Input;
+    type Answer1 = i32;
+    type Answer2 = i32;
+
+    fn parse(input: &str) -> Result<Input, ParseError> {
+        input.parse()
+    }
+
+    fn part1(input: Input) -> i32 {
+        part1(input)
+    }
+
+    fn part2(input: Input) -> i32 {
+        part2(input)
+    }
+}
+
 pub fn part1(input: Input) -> i32 {
     input.cards.into_iter()
         .filter_map(|c| if c.score > 0 { Some(2_i32.pow(c.score - 1)) } else { None })
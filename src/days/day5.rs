@@ -1,6 +1,9 @@
-use std::{convert::Infallible, str::FromStr};
+use std::str::FromStr;
 
-#[derive(Debug, PartialEq, Eq)]
+use crate::days::Solution;
+use crate::parse::{self, ParseError};
+
+#[derive(Debug, PartialEq, Eq, Clone)]
 struct Transform {
     rules: Vec<(i64, i64, i64)>,
 }
@@ -16,39 +19,62 @@ impl Transform {
     }
 }
 
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, PartialEq, Eq, Clone)]
 pub struct Input {
     seeds: Vec<i64>,
     transforms: Vec<Transform>,
 }
 
 impl FromStr for Transform {
-    type Err = Infallible;
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let rules = s
-            .lines()
-            .skip(1)
-            .map(|line| {
-                let v = line.split_whitespace().map(|n| n.parse::<i64>().unwrap()).collect::<Vec<_>>();
-                (v[0], v[1], v[2])
-            })
-            .collect();
+        let (_, rules) = s.split_once('\n').unwrap_or((s, ""));
+        let rules = parse::lines(rules, |line| {
+            let [to, from, range] = parse::numbers(line, line.split_whitespace())?;
+            Ok((to, from, range))
+        })
+        .map_err(|e| e.within(s, rules))?;
         Ok(Transform { rules })
     }
 }
 
 impl FromStr for Input {
-    type Err = Infallible;
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let (seeds, transfoms) = s.split_once("\n\n").unwrap();
-        let seeds = seeds.split_whitespace().skip(1).map(|n| n.parse::<i64>().unwrap()).collect();
-        let transforms = transfoms.split("\n\n").map(|t| Transform::from_str(t).unwrap()).collect();
+        let (seeds, transfoms) = parse::split_once(s, s, "\n\n")?;
+        let seeds = seeds
+            .split_whitespace()
+            .skip(1)
+            .map(|n| parse::number(s, n))
+            .collect::<Result<_, _>>()?;
+        let transforms = parse::blocks(transfoms, Transform::from_str).map_err(|e| e.within(s, transfoms))?;
         Ok(Input { seeds, transforms })
     }
 }
 
+pub struct Day5;
+
+impl Solution for Day5 {
+    const DAY: u32 = 5;
+    type Input = Input;
+    type Answer1 = i64;
+    type Answer2 = i64;
+
+    fn parse(input: &str) -> Result<Input, ParseError> {
+        input.parse()
+    }
+
+    fn part1(input: Input) -> i64 {
+        part1(input)
+    }
+
+    fn part2(input: Input) -> i64 {
+        part2(input)
+    }
+}
+
 pub fn part1(input: Input) -> i64 {
     input.seeds.into_iter()
         .map(|seed| map_to_location(seed, &input.transforms))
@@ -71,11 +97,11 @@ pub fn part2_naive(input: Input) -> i64 {
         .unwrap()
 }
 
-fn map_to_location(seed: i64, transforms: &Vec<Transform>) -> i64 {
+fn map_to_location(seed: i64, transforms: &[Transform]) -> i64 {
     transforms.iter().fold(seed, |i, transform| transform.process(i))
 }
 
-fn map_range_to_locations(start: i64, length: i64, transforms: &Vec<Transform>) -> Vec<(i64, i64)> {
+fn map_range_to_locations(start: i64, length: i64, transforms: &[Transform]) -> Vec<(i64, i64)> {
     let mut queue = Vec::new();
     queue.push((start, length));
     for transform in transforms {
@@ -112,7 +138,9 @@ fn range_transform(start: i64, length: i64, transform: &Transform) -> Vec<(i64,
     transformed
 }
 
-fn intersect_range(start: i64, length: i64, rule: &(i64, i64, i64)) -> (Option<(i64, i64)>, Option<(i64, i64)>, Option<(i64, i64)>) {
+type Range = Option<(i64, i64)>;
+
+fn intersect_range(start: i64, length: i64, rule: &(i64, i64, i64)) -> (Range, Range, Range) {
     let lo = start;
     let hi = start + length - 1;
     let rule_lo = rule.1;
@@ -1,31 +1,147 @@
-type Input<'a> = &'a [(i64, i64)];
+use std::str::FromStr;
 
-pub const INPUT1: &[(i64, i64)] = &[(56, 334), (71, 1135), (79, 1350), (99, 2430)];
-pub const INPUT2: &[(i64, i64)] = &[(56717999, 334113513502430)];
+use crate::days::Solution;
+use crate::parse::{self, ParseError};
 
-pub fn part1_2(input: Input) -> i64 {
-    input
-        .iter()
-        .map(|(t, record)| {
-            (1..*t)
-                .map(|i| i * (t - i))
-                .filter(|i| *i > *record)
-                .count() as i64
-        })
-        .product()
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+struct Race {
+    time: i64,
+    record: i64,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Input {
+    races: Vec<Race>,
+    /// The single race read by ignoring the spaces between numbers.
+    race: Race,
+}
+
+impl Race {
+    /// Number of button hold times `h` that beat the record, i.e. integers
+    /// with `h * (time - h) > record`. They lie strictly between the roots of
+    /// `h^2 - time * h + record`, which are found with an integer square root
+    /// and nudged so the bounds are exact.
+    fn ways_to_win(&self) -> i64 {
+        let Race { time, record } = *self;
+        let wins = |h: i64| h * (time - h) > record;
+        let discriminant = time * time - 4 * record;
+        if discriminant < 0 {
+            return 0;
+        }
+        let mut lo = ((time - discriminant.isqrt()) / 2).max(0);
+        while lo > 0 && wins(lo - 1) {
+            lo -= 1;
+        }
+        while lo <= time / 2 && !wins(lo) {
+            lo += 1;
+        }
+        (time - 2 * lo + 1).max(0)
+    }
+}
+
+impl FromStr for Input {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut lines = s.lines();
+        let mut row = |label: &str| {
+            let line = lines.next().unwrap_or(&s[s.len()..]);
+            let values = line
+                .strip_prefix(label)
+                .ok_or_else(|| ParseError::new(s, line, format!("`{label}`")))?;
+            let numbers = values
+                .split_whitespace()
+                .map(|n| parse::number(s, n))
+                .collect::<Result<Vec<i64>, _>>()?;
+            let joined: String = values.split_whitespace().collect();
+            let joined = joined.parse().map_err(|_| ParseError::new(s, values.trim(), "a number"))?;
+            Ok::<_, ParseError>((line, numbers, joined))
+        };
+        let (_, times, time) = row("Time:")?;
+        let (line, records, record) = row("Distance:")?;
+        if times.len() != records.len() {
+            return Err(ParseError::new(s, line, format!("{} distances", times.len())));
+        }
+        let races = times
+            .into_iter()
+            .zip(records)
+            .map(|(time, record)| Race { time, record })
+            .collect();
+        Ok(Input { races, race: Race { time, record } })
+    }
+}
+
+pub struct Day6;
+
+impl Solution for Day6 {
+    const DAY: u32 = 6;
+    type Input = Input;
+    type Answer1 = i64;
+    type Answer2 = i64;
+
+    fn parse(input: &str) -> Result<Input, ParseError> {
+        input.parse()
+    }
+
+    fn part1(input: Input) -> i64 {
+        part1(input)
+    }
+
+    fn part2(input: Input) -> i64 {
+        part2(input)
+    }
+}
+
+pub fn part1(input: Input) -> i64 {
+    input.races.iter().map(Race::ways_to_win).product()
+}
+
+pub fn part2(input: Input) -> i64 {
+    input.race.ways_to_win()
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    const INPUT: &str = "\
+Time:      7  15   30
+Distance:  9  40  200";
+
+    #[test]
+    fn input_from_str() {
+        let input = Input::from_str(INPUT).unwrap();
+        assert_eq!(Race { time: 15, record: 40 }, input.races[1]);
+        assert_eq!(Race { time: 71530, record: 940200 }, input.race);
+        let e = Input::from_str("Time: 7 15\nDistance: 9").unwrap_err();
+        assert_eq!((2, 1, "2 distances"), (e.line, e.column, e.expected.as_str()));
+        let e = Input::from_str("Time: 7 x\nDistance: 9 1").unwrap_err();
+        assert_eq!((1, 9), (e.line, e.column));
+    }
+
+    #[test]
+    fn test_ways_to_win() {
+        assert_eq!(4, Race { time: 7, record: 9 }.ways_to_win());
+        assert_eq!(8, Race { time: 15, record: 40 }.ways_to_win());
+        assert_eq!(9, Race { time: 30, record: 200 }.ways_to_win());
+        assert_eq!(0, Race { time: 4, record: 4 }.ways_to_win());
+        assert_eq!(1, Race { time: 4, record: 3 }.ways_to_win());
+        assert_eq!(0, Race { time: 3, record: 10 }.ways_to_win());
+        for time in 0..40 {
+            for record in 0..400 {
+                let expected = (0..=time).filter(|h| h * (time - h) > record).count() as i64;
+                assert_eq!(expected, Race { time, record }.ways_to_win(), "{time} {record}");
+            }
+        }
+    }
+
     #[test]
     fn test_part1() {
-        assert_eq!(288, part1_2(&[(7, 9), (15, 40), (30, 200)]));
+        assert_eq!(288, part1(Input::from_str(INPUT).unwrap()));
     }
 
     #[test]
     fn test_part2() {
-        assert_eq!(71503, part1_2(&[(71530, 940200)]));
+        assert_eq!(71503, part2(Input::from_str(INPUT).unwrap()));
     }
 }
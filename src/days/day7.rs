@@ -1,4 +1,7 @@
-use std::{collections::HashMap, convert::Infallible, str::FromStr};
+use std::{collections::HashMap, str::FromStr};
+
+use crate::days::Solution;
+use crate::parse::{self, ParseError};
 
 #[derive(Debug, PartialEq, Eq, Clone)]
 struct Hand {
@@ -6,7 +9,7 @@ struct Hand {
     value: usize,
 }
 
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, PartialEq, Eq, Clone)]
 pub struct Input {
     hands: Vec<Hand>,
 }
@@ -14,21 +17,27 @@ pub struct Input {
 const NUM_OF_CARDS: usize = 14;
 
 impl FromStr for Hand {
-    type Err = Infallible;
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let (cards, value) = s.split_once(' ').unwrap();
+        let (cards, value) = parse::split_once(s, s, " ")?;
+        if let Some((i, c)) = cards.char_indices().find(|&(_, c)| !"23456789TJQKA".contains(c)) {
+            return Err(ParseError::new(s, &cards[i..i + c.len_utf8()], "a card"));
+        }
+        if cards.len() != 5 {
+            return Err(ParseError::new(s, cards, "five cards"));
+        }
         let cards = cards.chars().collect();
-        let value = value.parse().unwrap();
+        let value = parse::number(s, value)?;
         Ok(Hand { cards, value })
     }
 }
 
 impl FromStr for Input {
-    type Err = Infallible;
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let hands = s.lines().map(|line| line.parse().unwrap()).collect();
+        let hands = parse::lines(s, Hand::from_str)?;
         Ok(Input { hands })
     }
 }
@@ -84,6 +93,27 @@ impl PartialOrd for Hand {
     }
 }
 
+pub struct Day7;
+
+impl Solution for Day7 {
+    const DAY: u32 = 7;
+    type Input = Input;
+    type Answer1 = usize;
+    type Answer2 = usize;
+
+    fn parse(input: &str) -> Result<Input, ParseError> {
+        input.parse()
+    }
+
+    fn part1(input: Input) -> usize {
+        part1(input)
+    }
+
+    fn part2(input: Input) -> usize {
+        part2(input)
+    }
+}
+
 pub fn part1(input: Input) -> usize {
     let mut hands = input.hands.clone();
     hands.sort_unstable();
@@ -120,6 +150,15 @@ KK677 28
 KTJJT 220
 QQQJA 483";
 
+    #[test]
+    fn hand_from_str_errors() {
+        let e = Hand::from_str("AAAAAA 5").unwrap_err();
+        assert_eq!((1, "AAAAAA", "five cards"), (e.column, e.found.as_str(), e.expected.as_str()));
+        let e = Hand::from_str("AK1QJ 5").unwrap_err();
+        assert_eq!((3, "1", "a card"), (e.column, e.found.as_str(), e.expected.as_str()));
+        assert!(Hand::from_str("AKQJ 5").is_err());
+    }
+
     #[test]
     fn test_part1() {
         assert_eq!(6440, part1(INPUT.parse().unwrap()));
@@ -1,8 +1,11 @@
+use crate::days::Solution;
+use crate::dot::{Graph, ToDot};
+use crate::parse::{self, ParseError};
 use crate::math::lcm_vec;
 use regex::Regex;
-use std::{collections::HashMap, str::FromStr, string::ParseError};
+use std::{collections::HashMap, str::FromStr};
 
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, PartialEq, Eq, Clone)]
 pub struct Input {
     directions: String,
     network: HashMap<String, (String, String)>,
@@ -13,13 +16,20 @@ impl FromStr for Input {
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         let re = Regex::new(r"([A-Z]{3}) = \(([A-Z]{3}), ([A-Z]{3})\)").unwrap();
-        let mut lines = s.lines();
-        let directions = lines.next().unwrap().to_owned();
-        _ = lines.next(); // empty line
-        let network = lines
-            .filter_map(|line| re.captures(line))
-            .map(|caps| (caps[1].to_owned(), (caps[2].to_owned(), caps[3].to_owned())))
-            .collect();
+        let (directions, network) = parse::split_once(s, s, "\n\n")?;
+        if let Some((i, c)) = directions.char_indices().find(|&(_, c)| c != 'L' && c != 'R') {
+            return Err(ParseError::new(s, &directions[i..i + c.len_utf8()], "`L` or `R`"));
+        }
+        let directions = directions.to_owned();
+        let network = parse::lines(network, |line| {
+            let caps = re
+                .captures(line)
+                .ok_or_else(|| ParseError::new(line, line, "`XXX = (YYY, ZZZ)`"))?;
+            Ok((caps[1].to_owned(), (caps[2].to_owned(), caps[3].to_owned())))
+        })
+        .map_err(|e| e.within(s, network))?
+        .into_iter()
+        .collect();
         Ok(Input {
             directions,
             network,
@@ -27,6 +37,49 @@ impl FromStr for Input {
     }
 }
 
+impl ToDot for Input {
+    /// Every node with its `L` and `R` edges; start (`..A`) and end (`..Z`)
+    /// nodes are highlighted.
+    fn to_dot(&self) -> Graph {
+        let mut graph = Graph::directed("day 8");
+        let mut names: Vec<_> = self.network.keys().collect();
+        names.sort();
+        for name in names {
+            let shape = match name.as_bytes()[2] {
+                b'A' => "doublecircle",
+                b'Z' => "box",
+                _ => "ellipse",
+            };
+            graph.node(name, &[("shape", shape)]);
+            let (left, right) = &self.network[name];
+            graph.edge(name, left, &[("label", "L")]);
+            graph.edge(name, right, &[("label", "R")]);
+        }
+        graph
+    }
+}
+
+pub struct Day8;
+
+impl Solution for Day8 {
+    const DAY: u32 = 8;
+    type Input = Input;
+    type Answer1 = i64;
+    type Answer2 = i64;
+
+    fn parse(input: &str) -> Result<Input, ParseError> {
+        input.parse()
+    }
+
+    fn part1(input: Input) -> i64 {
+        part1(input)
+    }
+
+    fn part2(input: Input) -> i64 {
+        part2(input)
+    }
+}
+
 pub fn part1(input: Input) -> i64 {
     let mut step = 0;
     let mut current_location: &String = &"AAA".to_owned();
@@ -49,7 +102,6 @@ pub fn part2(input: Input) -> i64 {
         .collect();
     let mut steps: Vec<i64> = Vec::new();
     for start in starts {
-        println!("{:?}", start);
         let mut step = 0;
         let mut current_location = start;
         while !current_location.ends_with("Z") {
@@ -60,7 +112,6 @@ pub fn part2(input: Input) -> i64 {
             };
             step += 1;
         }
-        println!("{step}");
         steps.push(step as i64);
     }
     lcm_vec(steps)
@@ -101,6 +152,24 @@ XXX = (XXX, XXX)";
         };
 
         assert_eq!(expected, Input::from_str(INPUT1).unwrap());
+        assert_eq!("→", Input::from_str("L→R\n\nAAA = (AAA, AAA)").unwrap_err().found);
+    }
+
+    #[test]
+    fn input_to_dot() {
+        let expected = "\
+digraph \"day 8\" {
+    \"AAA\" [shape=\"doublecircle\"];
+    \"AAA\" -> \"BBB\" [label=\"L\"];
+    \"AAA\" -> \"BBB\" [label=\"R\"];
+    \"BBB\" [shape=\"ellipse\"];
+    \"BBB\" -> \"AAA\" [label=\"L\"];
+    \"BBB\" -> \"ZZZ\" [label=\"R\"];
+    \"ZZZ\" [shape=\"box\"];
+    \"ZZZ\" -> \"ZZZ\" [label=\"L\"];
+    \"ZZZ\" -> \"ZZZ\" [label=\"R\"];
+}";
+        assert_eq!(expected, Input::from_str(INPUT1).unwrap().to_dot().to_string());
     }
 
     #[test]
@@ -1,6 +1,9 @@
-use std::{str::FromStr, string::ParseError};
+use std::str::FromStr;
 
-#[derive(Debug, PartialEq, Eq)]
+use crate::days::Solution;
+use crate::parse::{self, ParseError};
+
+#[derive(Debug, PartialEq, Eq, Clone)]
 pub struct Input {
     stories: Vec<Vec<i64>>,
 }
@@ -9,18 +12,36 @@ impl FromStr for Input {
     type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut stories = Vec::new();
-        for line in s.lines() {
-            let v = line
-                .split_whitespace()
-                .map(|n| n.parse::<i64>().unwrap())
-                .collect();
-            stories.push(v);
-        }
+        let stories = parse::lines(s, |line| {
+            line.split_whitespace()
+                .map(|n| parse::number(line, n))
+                .collect()
+        })?;
         Ok(Input { stories })
     }
 }
 
+pub struct Day9;
+
+impl Solution for Day9 {
+    const DAY: u32 = 9;
+    type Input = Input;
+    type Answer1 = i64;
+    type Answer2 = i64;
+
+    fn parse(input: &str) -> Result<Input, ParseError> {
+        input.parse()
+    }
+
+    fn part1(input: Input) -> i64 {
+        part1(input)
+    }
+
+    fn part2(input: Input) -> i64 {
+        part2(input)
+    }
+}
+
 pub fn part1(input: Input) -> i64 {
     input.stories.iter().map(|story| get_next(story)).sum()
 }
@@ -0,0 +1,89 @@
+use std::fmt;
+
+/// Inputs that can be drawn as a Graphviz graph.
+pub trait ToDot {
+    fn to_dot(&self) -> Graph;
+}
+
+/// Graph in the Graphviz DOT language, built node by node and edge by edge.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Graph {
+    name: String,
+    directed: bool,
+    statements: Vec<String>,
+}
+
+impl Graph {
+    pub fn directed(name: &str) -> Self {
+        Graph { name: name.to_owned(), directed: true, statements: Vec::new() }
+    }
+
+    pub fn undirected(name: &str) -> Self {
+        Graph { name: name.to_owned(), directed: false, statements: Vec::new() }
+    }
+
+    pub fn node(&mut self, id: &str, attributes: &[(&str, &str)]) -> &mut Self {
+        self.statements.push(format!("{}{}", quote(id), format_attributes(attributes)));
+        self
+    }
+
+    pub fn edge(&mut self, from: &str, to: &str, attributes: &[(&str, &str)]) -> &mut Self {
+        let arrow = if self.directed { "->" } else { "--" };
+        self.statements.push(format!("{} {arrow} {}{}", quote(from), quote(to), format_attributes(attributes)));
+        self
+    }
+}
+
+impl fmt::Display for Graph {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let kind = if self.directed { "digraph" } else { "graph" };
+        writeln!(f, "{kind} {} {{", quote(&self.name))?;
+        for statement in &self.statements {
+            writeln!(f, "    {statement};")?;
+        }
+        write!(f, "}}")
+    }
+}
+
+fn format_attributes(attributes: &[(&str, &str)]) -> String {
+    if attributes.is_empty() {
+        return String::new();
+    }
+    let attributes: Vec<_> = attributes
+        .iter()
+        .map(|(key, value)| format!("{key}={}", quote(value)))
+        .collect();
+    format!(" [{}]", attributes.join(", "))
+}
+
+fn quote(s: &str) -> String {
+    format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_display() {
+        let mut graph = Graph::directed("day 0");
+        graph
+            .node("a", &[("shape", "box"), ("label", "%a")])
+            .edge("a", "b\"", &[])
+            .edge("b\"", "a", &[("label", "x<1")]);
+        let expected = "\
+digraph \"day 0\" {
+    \"a\" [shape=\"box\", label=\"%a\"];
+    \"a\" -> \"b\\\"\";
+    \"b\\\"\" -> \"a\" [label=\"x<1\"];
+}";
+        assert_eq!(expected, graph.to_string());
+    }
+
+    #[test]
+    fn test_undirected() {
+        let mut graph = Graph::undirected("g");
+        graph.edge("a", "b", &[("label", "3")]);
+        assert_eq!("graph \"g\" {\n    \"a\" -- \"b\" [label=\"3\"];\n}", graph.to_string());
+    }
+}
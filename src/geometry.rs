@@ -1,13 +1,242 @@
-pub fn manhattan_distance_i64(a: (i64, i64), b: (i64, i64)) -> i64 {
-    (a.0.abs_diff(b.0) + a.1.abs_diff(b.1)) as i64
+use std::{fmt, ops::{Add, AddAssign, Index, IndexMut, Mul, Neg, Sub, SubAssign}};
+
+use crate::parse::{self, ParseError};
+
+/// 2D point; grid positions use `(row, col)` order like `Direction`.
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, Default, PartialOrd, Ord)]
+pub struct Point<T>(pub T, pub T);
+
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, Default, PartialOrd, Ord)]
+pub struct Point3<T>(pub T, pub T, pub T);
+
+fn abs_diff<T: Copy + PartialOrd + Sub<Output = T>>(a: T, b: T) -> T {
+    if a > b { a - b } else { b - a }
+}
+
+impl<T: Copy + PartialOrd + Add<Output = T> + Sub<Output = T>> Point<T> {
+    pub fn manhattan_distance(self, other: Self) -> T {
+        abs_diff(self.0, other.0) + abs_diff(self.1, other.1)
+    }
+
+    pub fn chebyshev_distance(self, other: Self) -> T {
+        let (d0, d1) = (abs_diff(self.0, other.0), abs_diff(self.1, other.1));
+        if d0 > d1 { d0 } else { d1 }
+    }
+}
+
+impl<T> Point<T> {
+    pub fn map<U>(self, f: impl Fn(T) -> U) -> Point<U> {
+        Point(f(self.0), f(self.1))
+    }
+}
+
+impl<T: Copy + PartialOrd + Add<Output = T> + Sub<Output = T>> Point3<T> {
+    pub fn manhattan_distance(self, other: Self) -> T {
+        abs_diff(self.0, other.0) + abs_diff(self.1, other.1) + abs_diff(self.2, other.2)
+    }
+
+    pub fn chebyshev_distance(self, other: Self) -> T {
+        [abs_diff(self.1, other.1), abs_diff(self.2, other.2)]
+            .into_iter()
+            .fold(abs_diff(self.0, other.0), |max, d| if d > max { d } else { max })
+    }
+}
+
+impl<T: Copy + Add<Output = T> + Sub<Output = T> + Mul<Output = T>> Point3<T> {
+    pub fn dot(self, other: Self) -> T {
+        self.0 * other.0 + self.1 * other.1 + self.2 * other.2
+    }
+
+    pub fn cross(self, other: Self) -> Self {
+        Point3(
+            self.1 * other.2 - self.2 * other.1,
+            self.2 * other.0 - self.0 * other.2,
+            self.0 * other.1 - self.1 * other.0,
+        )
+    }
+}
+
+impl<T> Point3<T> {
+    pub fn map<U>(self, f: impl Fn(T) -> U) -> Point3<U> {
+        Point3(f(self.0), f(self.1), f(self.2))
+    }
+
+    /// Drops the third coordinate.
+    pub fn xy(self) -> Point<T> {
+        Point(self.0, self.1)
+    }
+}
+
+impl<T: Add<Output = T>> Add for Point<T> {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Point(self.0 + other.0, self.1 + other.1)
+    }
+}
+
+impl<T: Sub<Output = T>> Sub for Point<T> {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        Point(self.0 - other.0, self.1 - other.1)
+    }
+}
+
+impl<T: Copy + Mul<Output = T>> Mul<T> for Point<T> {
+    type Output = Self;
+
+    fn mul(self, k: T) -> Self {
+        Point(self.0 * k, self.1 * k)
+    }
 }
 
-pub fn manhattan_distance_usize(a: (usize, usize), b: (usize, usize)) -> usize {
-    a.0.abs_diff(b.0) + a.1.abs_diff(b.1)
+impl<T: Neg<Output = T>> Neg for Point<T> {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Point(-self.0, -self.1)
+    }
+}
+
+impl<T: Copy + Add<Output = T>> AddAssign for Point<T> {
+    fn add_assign(&mut self, other: Self) {
+        *self = *self + other;
+    }
+}
+
+impl<T: Copy + Sub<Output = T>> SubAssign for Point<T> {
+    fn sub_assign(&mut self, other: Self) {
+        *self = *self - other;
+    }
+}
+
+impl<T: Add<Output = T>> Add for Point3<T> {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Point3(self.0 + other.0, self.1 + other.1, self.2 + other.2)
+    }
+}
+
+impl<T: Sub<Output = T>> Sub for Point3<T> {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        Point3(self.0 - other.0, self.1 - other.1, self.2 - other.2)
+    }
+}
+
+impl<T: Copy + Mul<Output = T>> Mul<T> for Point3<T> {
+    type Output = Self;
+
+    fn mul(self, k: T) -> Self {
+        Point3(self.0 * k, self.1 * k, self.2 * k)
+    }
+}
+
+impl<T: Neg<Output = T>> Neg for Point3<T> {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Point3(-self.0, -self.1, -self.2)
+    }
+}
+
+impl<T: Copy + Add<Output = T>> AddAssign for Point3<T> {
+    fn add_assign(&mut self, other: Self) {
+        *self = *self + other;
+    }
+}
+
+impl<T: Copy + Sub<Output = T>> SubAssign for Point3<T> {
+    fn sub_assign(&mut self, other: Self) {
+        *self = *self - other;
+    }
+}
+
+impl Add<Direction> for Point<i32> {
+    type Output = Self;
+
+    fn add(self, dir: Direction) -> Self {
+        Point(self.0 + dir.0, self.1 + dir.1)
+    }
+}
+
+impl AddAssign<Direction> for Point<i32> {
+    fn add_assign(&mut self, dir: Direction) {
+        *self = *self + dir;
+    }
+}
+
+/// Grid positions are plain `(row, col)` tuples, so they can be moved too.
+impl Add<Direction> for (i32, i32) {
+    type Output = Self;
+
+    fn add(self, dir: Direction) -> Self {
+        (self.0 + dir.0, self.1 + dir.1)
+    }
+}
+
+impl Sub<Direction> for (i32, i32) {
+    type Output = Self;
+
+    fn sub(self, dir: Direction) -> Self {
+        (self.0 - dir.0, self.1 - dir.1)
+    }
+}
+
+impl<T> From<(T, T)> for Point<T> {
+    fn from((a, b): (T, T)) -> Self {
+        Point(a, b)
+    }
+}
+
+impl<T> From<Point<T>> for (T, T) {
+    fn from(p: Point<T>) -> Self {
+        (p.0, p.1)
+    }
+}
+
+impl<T> From<(T, T, T)> for Point3<T> {
+    fn from((a, b, c): (T, T, T)) -> Self {
+        Point3(a, b, c)
+    }
+}
+
+impl<T> From<Point3<T>> for (T, T, T) {
+    fn from(p: Point3<T>) -> Self {
+        (p.0, p.1, p.2)
+    }
+}
+
+impl From<Direction> for Point<i32> {
+    fn from(dir: Direction) -> Self {
+        Point(dir.0, dir.1)
+    }
 }
 
 pub fn get_neighbors(pos: (i32, i32)) -> impl Iterator<Item = (i32, i32)> {
-    Direction::iter().map(move |dir| (pos.0 + dir.0, pos.1 + dir.1))
+    Direction::iter().map(move |dir| pos + dir)
+}
+
+/// Orthogonal and diagonal neighbors of `pos`.
+pub fn get_all_neighbors(pos: (i32, i32)) -> impl Iterator<Item = (i32, i32)> {
+    Direction::iter_all().map(move |dir| pos + dir)
+}
+
+/// Whether `pos` lies within a `(rows, cols)` area starting at the origin.
+pub fn is_in_bounds(pos: (i32, i32), size: (i32, i32)) -> bool {
+    pos.0 >= 0 && pos.0 < size.0 &&
+    pos.1 >= 0 && pos.1 < size.1
+}
+
+pub fn get_bounded_neighbors(pos: (i32, i32), size: (i32, i32)) -> impl Iterator<Item = (i32, i32)> {
+    get_neighbors(pos).filter(move |&n| is_in_bounds(n, size))
+}
+
+pub fn get_all_bounded_neighbors(pos: (i32, i32), size: (i32, i32)) -> impl Iterator<Item = (i32, i32)> {
+    get_all_neighbors(pos).filter(move |&n| is_in_bounds(n, size))
 }
 
 #[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
@@ -17,23 +246,219 @@ pub const RIGHT: Direction = Direction(0, 1);
 pub const LEFT: Direction = Direction(0, -1);
 pub const DOWN: Direction = Direction(1, 0);
 pub const UP: Direction = Direction(-1, 0);
+pub const DOWN_RIGHT: Direction = Direction(1, 1);
+pub const DOWN_LEFT: Direction = Direction(1, -1);
+pub const UP_RIGHT: Direction = Direction(-1, 1);
+pub const UP_LEFT: Direction = Direction(-1, -1);
 
 impl Direction {
     pub fn reverse(&self) -> Self {
-        Self(self.0 * -1, self.1 * -1)
+        Self(-self.0, -self.1)
     }
 
     pub fn turn_right(&self) -> Self {
-        Self(self.1, self.0 * -1)
+        Self(self.1, -self.0)
     }
 
     pub fn turn_left(&self) -> Self {
-        Self(self.1 * -1, self.0)
+        Self(-self.1, self.0)
+    }
+
+    /// Turns an eighth of a circle clockwise, e.g. `RIGHT` to `DOWN_RIGHT`.
+    pub fn turn_right_45(&self) -> Self {
+        let turned = self.turn_right();
+        Self((self.0 + turned.0).signum(), (self.1 + turned.1).signum())
+    }
+
+    /// Turns an eighth of a circle counter-clockwise, e.g. `RIGHT` to `UP_RIGHT`.
+    pub fn turn_left_45(&self) -> Self {
+        let turned = self.turn_left();
+        Self((self.0 + turned.0).signum(), (self.1 + turned.1).signum())
+    }
+
+    pub fn is_diagonal(&self) -> bool {
+        self.0 != 0 && self.1 != 0
     }
 
     pub fn iter() -> impl Iterator<Item = Direction> {
         [RIGHT, LEFT, DOWN, UP].into_iter()
     }
+
+    pub fn diagonals() -> impl Iterator<Item = Direction> {
+        [DOWN_RIGHT, DOWN_LEFT, UP_RIGHT, UP_LEFT].into_iter()
+    }
+
+    /// Orthogonal directions followed by diagonal ones.
+    pub fn iter_all() -> impl Iterator<Item = Direction> {
+        Direction::iter().chain(Direction::diagonals())
+    }
+}
+
+/// Rectangular grid stored row-major and indexed by `(row, col)` positions.
+#[derive(Debug, PartialEq, Eq, Hash, Clone)]
+pub struct Grid<T> {
+    cells: Vec<T>,
+    height: usize,
+    width: usize,
+}
+
+impl<T> Grid<T> {
+    pub fn new(height: usize, width: usize, cells: Vec<T>) -> Self {
+        assert_eq!(height * width, cells.len(), "grid cells do not match its size");
+        Grid { cells, height, width }
+    }
+
+    pub fn filled(height: usize, width: usize, value: T) -> Self
+    where
+        T: Clone,
+    {
+        Grid::new(height, width, vec![value; height * width])
+    }
+
+    /// Parses a rectangular character map, `f` returning `None` for
+    /// characters that are not `expected`.
+    pub fn parse<F>(s: &str, expected: &str, f: F) -> Result<Self, ParseError>
+    where
+        F: Fn(char) -> Option<T>,
+    {
+        let rows = parse::map(s, expected, f)?;
+        let width = rows.first().map_or(0, |row| row.len());
+        if let Some((_, line)) = rows.iter().zip(s.lines()).find(|(row, _)| row.len() != width) {
+            return Err(ParseError::new(s, line, format!("a row of {width} tiles")));
+        }
+        Ok(Grid::new(rows.len(), width, rows.into_iter().flatten().collect()))
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn size(&self) -> (i32, i32) {
+        (self.height as i32, self.width as i32)
+    }
+
+    pub fn is_in(&self, pos: (i32, i32)) -> bool {
+        is_in_bounds(pos, self.size())
+    }
+
+    fn index_of(&self, pos: (i32, i32)) -> Option<usize> {
+        self.is_in(pos).then(|| pos.0 as usize * self.width + pos.1 as usize)
+    }
+
+    pub fn get(&self, pos: (i32, i32)) -> Option<&T> {
+        self.index_of(pos).map(|i| &self.cells[i])
+    }
+
+    pub fn get_mut(&mut self, pos: (i32, i32)) -> Option<&mut T> {
+        self.index_of(pos).map(|i| &mut self.cells[i])
+    }
+
+    pub fn positions(&self) -> impl Iterator<Item = (i32, i32)> {
+        let (height, width) = self.size();
+        (0..height).flat_map(move |row| (0..width).map(move |col| (row, col)))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = ((i32, i32), &T)> {
+        self.positions().zip(&self.cells)
+    }
+
+    /// Position of the first cell, in row-major order, matching `f`.
+    pub fn find(&self, f: impl Fn(&T) -> bool) -> Option<(i32, i32)> {
+        self.iter().find_map(|(pos, cell)| f(cell).then_some(pos))
+    }
+
+    pub fn row(&self, row: usize) -> &[T] {
+        &self.cells[row * self.width..(row + 1) * self.width]
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        self.cells.chunks(self.width.max(1))
+    }
+
+    pub fn column(&self, col: usize) -> impl Iterator<Item = &T> {
+        self.cells.iter().skip(col).step_by(self.width.max(1))
+    }
+
+    pub fn columns(&self) -> impl Iterator<Item = impl Iterator<Item = &T>> {
+        (0..self.width).map(|col| self.column(col))
+    }
+
+    /// In-bounds orthogonal neighbors of `pos`.
+    pub fn neighbors(&self, pos: (i32, i32)) -> impl Iterator<Item = (i32, i32)> {
+        get_bounded_neighbors(pos, self.size())
+    }
+
+    /// In-bounds orthogonal and diagonal neighbors of `pos`.
+    pub fn all_neighbors(&self, pos: (i32, i32)) -> impl Iterator<Item = (i32, i32)> {
+        get_all_bounded_neighbors(pos, self.size())
+    }
+
+    pub fn map<U>(&self, f: impl Fn(&T) -> U) -> Grid<U> {
+        Grid::new(self.height, self.width, self.cells.iter().map(f).collect())
+    }
+
+    pub fn transpose(&self) -> Self
+    where
+        T: Clone,
+    {
+        let cells = self.columns().flatten().cloned().collect();
+        Grid::new(self.width, self.height, cells)
+    }
+
+    /// Rotates the grid a quarter turn clockwise.
+    pub fn rotate_right(&self) -> Self
+    where
+        T: Clone,
+    {
+        let cells = (0..self.width)
+            .flat_map(|col| (0..self.height).rev().map(move |row| self.row(row)[col].clone()))
+            .collect();
+        Grid::new(self.width, self.height, cells)
+    }
+
+    /// Rotates the grid a quarter turn counter-clockwise.
+    pub fn rotate_left(&self) -> Self
+    where
+        T: Clone,
+    {
+        let cells = (0..self.width)
+            .rev()
+            .flat_map(|col| (0..self.height).map(move |row| self.row(row)[col].clone()))
+            .collect();
+        Grid::new(self.width, self.height, cells)
+    }
+}
+
+impl<T> Index<(i32, i32)> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, pos: (i32, i32)) -> &T {
+        self.get(pos).unwrap_or_else(|| panic!("{pos:?} is outside of the grid"))
+    }
+}
+
+impl<T> IndexMut<(i32, i32)> for Grid<T> {
+    fn index_mut(&mut self, pos: (i32, i32)) -> &mut T {
+        self.get_mut(pos).unwrap_or_else(|| panic!("{pos:?} is outside of the grid"))
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for Grid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, row) in self.rows().enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+            for cell in row {
+                write!(f, "{cell}")?;
+            }
+        }
+        Ok(())
+    }
 }
 
 #[cfg(test)]
@@ -42,8 +467,8 @@ mod tests {
 
     #[test]
     fn test_manhattan_distance() {
-        assert_eq!(25, manhattan_distance_i64((-2, 8), (10, -5)));
-        assert_eq!(11, manhattan_distance_usize((2, 8), (10, 5)));
+        assert_eq!(25, Point(-2_i64, 8).manhattan_distance(Point(10, -5)));
+        assert_eq!(11, Point(2_usize, 8).manhattan_distance(Point(10, 5)));
     }
 
     #[test]
@@ -70,9 +495,120 @@ mod tests {
         assert_eq!(UP, RIGHT.turn_left());
     }
 
+    #[test]
+    fn point_ops() {
+        let (a, b) = (Point(1, 2), Point(4, -2));
+        assert_eq!(Point(5, 0), a + b);
+        assert_eq!(Point(-3, 4), a - b);
+        assert_eq!(Point(3, 6), a * 3);
+        assert_eq!(Point(-1, -2), -a);
+        assert_eq!(Point(1, 3), a + RIGHT);
+        assert_eq!((0, 2), (1, 2) + UP);
+        let mut c = a;
+        c += b;
+        c -= Point(1, 1);
+        assert_eq!(Point(4, -1), c);
+        assert_eq!(Point3(2, 4, 6), Point3(1, 1, 1) + Point3(1, 3, 5));
+        assert_eq!(Point3(0, 1, 2), (Point3(1, 2, 3) - Point3(1, 1, 1)) * 1);
+        assert_eq!(Point3(0, 0, 1), Point3(1, 0, 0).cross(Point3(0, 1, 0)));
+        assert_eq!(Point3(-3, 6, -3), Point3(1, 2, 3).cross(Point3(4, 5, 6)));
+        assert_eq!(32, Point3(1, 2, 3).dot(Point3(4, 5, 6)));
+    }
+
+    #[test]
+    fn point_distances() {
+        assert_eq!(7, Point(1, 2).manhattan_distance(Point(4, -2)));
+        assert_eq!(4, Point(1, 2).chebyshev_distance(Point(4, -2)));
+        assert_eq!(5_usize, Point(3_usize, 1).manhattan_distance(Point(1, 4)));
+        assert_eq!(9, Point3(0, 0, 0).manhattan_distance(Point3(2, -3, 4)));
+        assert_eq!(4, Point3(0, 0, 0).chebyshev_distance(Point3(2, -3, 4)));
+    }
+
+    #[test]
+    fn point_conversions() {
+        assert_eq!(Point(1, 2), Point::from((1, 2)));
+        assert_eq!((1, 2), <(i32, i32)>::from(Point(1, 2)));
+        assert_eq!(Point3(1, 2, 3), (1, 2, 3).into());
+        assert_eq!(Point(0, -1), Point::from(LEFT));
+        assert_eq!(Point(1_i64, 2), Point3(1, 2, 3).xy().map(i64::from));
+    }
+
+    const GRID: &str = "\
+abc
+def";
+
+    #[test]
+    fn grid_parse() {
+        let grid = Grid::parse(GRID, "a letter", |c| c.is_ascii_lowercase().then_some(c)).unwrap();
+        assert_eq!((2, 3), grid.size());
+        assert_eq!('a', grid[(0, 0)]);
+        assert_eq!('f', grid[(1, 2)]);
+        assert_eq!(None, grid.get((2, 0)));
+        assert_eq!(None, grid.get((0, -1)));
+        assert_eq!(GRID, grid.to_string());
+
+        let e = Grid::parse("ab\nc", "a letter", Some).unwrap_err();
+        assert_eq!((2, 1, "a row of 2 tiles"), (e.line, e.column, e.expected.as_str()));
+        let e = Grid::parse("ab\n1b", "a letter", |c| c.is_ascii_lowercase().then_some(c)).unwrap_err();
+        assert_eq!((2, 1), (e.line, e.column));
+    }
+
+    #[test]
+    fn grid_rows_and_columns() {
+        let grid = Grid::parse(GRID, "", Some).unwrap();
+        assert_eq!(vec!["abc", "def"], grid.rows().map(|r| r.iter().collect::<String>()).collect::<Vec<_>>());
+        assert_eq!(vec!["ad", "be", "cf"], grid.columns().map(|c| c.collect::<String>()).collect::<Vec<_>>());
+        assert_eq!(Some((1, 1)), grid.find(|&c| c == 'e'));
+        assert_eq!(6, grid.iter().count());
+    }
+
+    #[test]
+    fn grid_neighbors() {
+        let grid = Grid::parse(GRID, "", Some).unwrap();
+        assert_eq!(vec![(0, 1), (1, 0)], grid.neighbors((0, 0)).collect::<Vec<_>>());
+        assert_eq!(vec![(1, 2), (1, 0), (0, 1)], grid.neighbors((1, 1)).collect::<Vec<_>>());
+        assert_eq!(vec![(0, 1), (1, 0), (1, 1)], grid.all_neighbors((0, 0)).collect::<Vec<_>>());
+        assert_eq!(5, grid.all_neighbors((1, 1)).count());
+    }
+
+    #[test]
+    fn grid_transform() {
+        let grid = Grid::parse(GRID, "", Some).unwrap();
+        assert_eq!("ad\nbe\ncf", grid.transpose().to_string());
+        assert_eq!("da\neb\nfc", grid.rotate_right().to_string());
+        assert_eq!("cf\nbe\nad", grid.rotate_left().to_string());
+        assert_eq!(grid, grid.rotate_right().rotate_left());
+        assert_eq!(grid, grid.rotate_right().rotate_right().rotate_right().rotate_right());
+    }
+
     #[test]
     fn test_get_neighbors() {
         let expected = vec![(5, 6), (5, 4), (6, 5), (4, 5)];
         assert_eq!(expected, get_neighbors((5, 5)).collect::<Vec<_>>());
     }
+
+    #[test]
+    fn test_get_all_neighbors() {
+        let expected = vec![(5, 6), (5, 4), (6, 5), (4, 5), (6, 6), (6, 4), (4, 6), (4, 4)];
+        assert_eq!(expected, get_all_neighbors((5, 5)).collect::<Vec<_>>());
+        assert_eq!(vec![(0, 1), (1, 0), (1, 1)], get_all_bounded_neighbors((0, 0), (2, 2)).collect::<Vec<_>>());
+        assert_eq!(vec![(1, 0), (0, 1)], get_bounded_neighbors((1, 1), (2, 2)).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_turns() {
+        assert_eq!(DOWN_RIGHT, UP_RIGHT.turn_right());
+        assert_eq!(UP_LEFT, DOWN_RIGHT.reverse());
+        assert_eq!(DOWN_RIGHT, RIGHT.turn_right_45());
+        assert_eq!(DOWN, DOWN_RIGHT.turn_right_45());
+        assert_eq!(UP_RIGHT, RIGHT.turn_left_45());
+        assert_eq!(UP, UP_RIGHT.turn_left_45());
+        let mut dir = UP;
+        for _ in 0..8 {
+            assert_eq!(dir, dir.turn_right_45().turn_left_45());
+            dir = dir.turn_right_45();
+        }
+        assert_eq!(UP, dir);
+        assert!(UP_LEFT.is_diagonal() && !LEFT.is_diagonal());
+    }
 }
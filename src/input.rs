@@ -1,13 +1,283 @@
-use std::fs;
-
-pub fn read_from_file(day: i32) -> String {
-    fs::read_to_string(format!("./input/{day}.txt")).unwrap()
-}
-
-#[cfg(test)]
-mod tests {
-    #[test]
-    fn read_from_file() {
-        assert_eq!("69", super::read_from_file(0));
-    }
-}
+use std::{
+    env, fmt, fs,
+    io::{self, Read},
+    path::{Path, PathBuf},
+};
+
+/// Environment variable overriding the directory puzzle inputs are read from.
+pub const ROOT_VAR: &str = "AOC_INPUT_DIR";
+pub const DEFAULT_ROOT: &str = "./input";
+/// Environment variable enabling the `<year>/<day>.txt` input cache.
+pub const CACHE_VAR: &str = "AOC_CACHE_DIR";
+pub const YEAR: u32 = 2023;
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum Source {
+    File(PathBuf),
+    Stdin,
+}
+
+impl Source {
+    /// `-` stands for stdin, anything else is a file path.
+    pub fn from_arg(arg: &str) -> Self {
+        match arg {
+            "-" => Source::Stdin,
+            path => Source::File(PathBuf::from(path)),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum InputError {
+    NotFound(PathBuf),
+    Io(Option<PathBuf>, io::Error),
+    Fetch(u32, u32, String),
+}
+
+impl fmt::Display for InputError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InputError::NotFound(path) => write!(
+                f,
+                "no input file at {}; save the puzzle input there, pass --input <PATH> or set {ROOT_VAR}",
+                path.display()
+            ),
+            InputError::Io(Some(path), e) => write!(f, "cannot read {}: {e}", path.display()),
+            InputError::Io(None, e) => write!(f, "cannot read stdin: {e}"),
+            InputError::Fetch(year, day, reason) => write!(f, "cannot fetch input for {year} day {day}: {reason}"),
+        }
+    }
+}
+
+impl std::error::Error for InputError {}
+
+/// Backend that obtains a day's input when it is missing from the cache, e.g.
+/// by downloading it from the puzzle website.
+pub trait Fetcher {
+    fn fetch(&self, year: u32, day: u32) -> Result<String, InputError>;
+}
+
+/// Fetcher copying inputs out of a local `<year>/<day>.txt` directory tree.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct LocalFetcher {
+    root: PathBuf,
+}
+
+impl LocalFetcher {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        LocalFetcher { root: root.into() }
+    }
+}
+
+impl Fetcher for LocalFetcher {
+    fn fetch(&self, year: u32, day: u32) -> Result<String, InputError> {
+        let path = self.root.join(year.to_string()).join(format!("{day}.txt"));
+        fs::read_to_string(&path).map_err(|e| InputError::Fetch(year, day, format!("{}: {e}", path.display())))
+    }
+}
+
+/// Resolves inputs from a cache directory keyed by year and day, asking the
+/// fetcher (if any) for missing ones and storing what it returns.
+pub struct Provider {
+    cache: PathBuf,
+    fetcher: Option<Box<dyn Fetcher>>,
+}
+
+impl Provider {
+    pub fn new(cache: impl Into<PathBuf>) -> Self {
+        Provider { cache: cache.into(), fetcher: None }
+    }
+
+    pub fn with_fetcher(mut self, fetcher: impl Fetcher + 'static) -> Self {
+        self.fetcher = Some(Box::new(fetcher));
+        self
+    }
+
+    pub fn cache_path(&self, year: u32, day: u32) -> PathBuf {
+        self.cache.join(year.to_string()).join(format!("{day}.txt"))
+    }
+
+    pub fn get(&self, year: u32, day: u32) -> Result<String, InputError> {
+        let path = self.cache_path(year, day);
+        match (read_file(&path), &self.fetcher) {
+            (Err(InputError::NotFound(_)), Some(fetcher)) => {
+                let input = fetcher.fetch(year, day)?;
+                let write = |path: &Path| {
+                    fs::create_dir_all(self.cache.join(year.to_string()))?;
+                    fs::write(path, &input)
+                };
+                write(&path).map_err(|e| InputError::Io(Some(path.clone()), e))?;
+                Ok(normalize(&input))
+            }
+            (result, _) => result,
+        }
+    }
+}
+
+/// Where inputs are looked up when no explicit source is given: the cache
+/// provider when configured, then `<root>/<day>.txt`.
+pub struct Inputs {
+    root: PathBuf,
+    provider: Option<Provider>,
+}
+
+impl Inputs {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Inputs { root: root.into(), provider: None }
+    }
+
+    pub fn with_provider(mut self, provider: Provider) -> Self {
+        self.provider = Some(provider);
+        self
+    }
+
+    /// Reads `source`, or the given day's default input when there is none.
+    pub fn read(&self, source: Option<&Source>, day: u32) -> Result<String, InputError> {
+        match (source, &self.provider) {
+            (Some(Source::File(path)), _) => read_file(path),
+            (Some(Source::Stdin), _) => read_stdin(),
+            (None, Some(provider)) => match provider.get(YEAR, day) {
+                Err(InputError::NotFound(_)) => read_file(&path(&self.root, day)),
+                result => result,
+            },
+            (None, None) => read_file(&path(&self.root, day)),
+        }
+    }
+}
+
+/// Input directory: `dir` if given, else `$AOC_INPUT_DIR`, else `./input`.
+pub fn root(dir: Option<&Path>) -> PathBuf {
+    match dir {
+        Some(dir) => dir.to_owned(),
+        None => env::var_os(ROOT_VAR).map_or(PathBuf::from(DEFAULT_ROOT), PathBuf::from),
+    }
+}
+
+pub fn path(root: &Path, day: u32) -> PathBuf {
+    root.join(format!("{day}.txt"))
+}
+
+/// Cache directory: `dir` if given, else `$AOC_CACHE_DIR`, else none.
+pub fn cache_root(dir: Option<&Path>) -> Option<PathBuf> {
+    dir.map(Path::to_owned).or_else(|| env::var_os(CACHE_VAR).map(PathBuf::from))
+}
+
+pub fn read_from_file(day: u32) -> Result<String, InputError> {
+    Inputs::new(root(None)).read(None, day)
+}
+
+pub fn read_file(path: &Path) -> Result<String, InputError> {
+    match fs::read_to_string(path) {
+        Ok(s) => Ok(normalize(&s)),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Err(InputError::NotFound(path.to_owned())),
+        Err(e) => Err(InputError::Io(Some(path.to_owned()), e)),
+    }
+}
+
+pub fn read_stdin() -> Result<String, InputError> {
+    let mut s = String::new();
+    io::stdin()
+        .read_to_string(&mut s)
+        .map_err(|e| InputError::Io(None, e))?;
+    Ok(normalize(&s))
+}
+
+/// Converts CRLF line endings to LF and drops trailing newlines.
+pub fn normalize(s: &str) -> String {
+    s.replace("\r\n", "\n").trim_end_matches('\n').to_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = env::temp_dir().join(format!("aoc-2023-rust-{name}-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn read_from_root() {
+        let root = temp_dir("input-read");
+        fs::write(path(&root, 1), "69\r\n420\r\n\n").unwrap();
+        assert_eq!("69\n420", Inputs::new(&root).read(None, 1).unwrap());
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn read_missing() {
+        let root = temp_dir("input-missing");
+        let e = Inputs::new(&root).read(None, 25).unwrap_err();
+        assert!(matches!(&e, InputError::NotFound(p) if *p == path(&root, 25)));
+        assert!(e.to_string().contains("25.txt"));
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn provider_reads_cache() {
+        let cache = temp_dir("provider-cache");
+        fs::create_dir_all(cache.join("2023")).unwrap();
+        fs::write(cache.join("2023").join("3.txt"), "cached\n").unwrap();
+        let provider = Provider::new(&cache);
+        assert_eq!("cached", provider.get(2023, 3).unwrap());
+        assert!(matches!(provider.get(2023, 4), Err(InputError::NotFound(_))));
+        fs::remove_dir_all(cache).unwrap();
+    }
+
+    #[test]
+    fn provider_fetches_and_caches() {
+        let cache = temp_dir("provider-fetch-cache");
+        let fixtures = temp_dir("provider-fetch-fixtures");
+        fs::create_dir_all(fixtures.join("2023")).unwrap();
+        fs::write(fixtures.join("2023").join("7.txt"), "fetched\r\n").unwrap();
+        let provider = Provider::new(&cache).with_fetcher(LocalFetcher::new(&fixtures));
+
+        assert_eq!("fetched", provider.get(2023, 7).unwrap());
+        assert!(provider.cache_path(2023, 7).exists());
+        fs::remove_dir_all(&fixtures).unwrap();
+        assert_eq!("fetched", provider.get(2023, 7).unwrap());
+        assert!(matches!(provider.get(2023, 8), Err(InputError::Fetch(2023, 8, _))));
+        fs::remove_dir_all(cache).unwrap();
+    }
+
+    #[test]
+    fn inputs_prefer_provider() {
+        let cache = temp_dir("inputs-provider");
+        fs::create_dir_all(cache.join("2023")).unwrap();
+        fs::write(cache.join("2023").join("5.txt"), "from cache").unwrap();
+        let inputs = Inputs::new(cache.join("flat")).with_provider(Provider::new(&cache));
+        assert_eq!("from cache", inputs.read(None, 5).unwrap());
+        fs::remove_dir_all(cache).unwrap();
+    }
+
+    #[test]
+    fn inputs_fall_back_to_root() {
+        let cache = temp_dir("inputs-fallback-cache");
+        let root = temp_dir("inputs-fallback-root");
+        fs::write(path(&root, 5), "from root").unwrap();
+        let inputs = Inputs::new(&root).with_provider(Provider::new(&cache));
+        assert_eq!("from root", inputs.read(None, 5).unwrap());
+        assert!(matches!(inputs.read(None, 6), Err(InputError::NotFound(p)) if p == path(&root, 6)));
+        fs::remove_dir_all(cache).unwrap();
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn root_prefers_explicit_dir() {
+        assert_eq!(PathBuf::from("elsewhere"), root(Some(Path::new("elsewhere"))));
+    }
+
+    #[test]
+    fn test_normalize() {
+        assert_eq!("a\nb", normalize("a\r\nb\r\n"));
+        assert_eq!("a\n\nb", normalize("a\n\nb\n\n"));
+        assert_eq!("", normalize("\n"));
+    }
+
+    #[test]
+    fn source_from_arg() {
+        assert_eq!(Source::Stdin, Source::from_arg("-"));
+        assert_eq!(Source::File(PathBuf::from("in.txt")), Source::from_arg("in.txt"));
+    }
+}
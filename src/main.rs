@@ -1,21 +1,189 @@
 #![allow(dead_code)]
 
+mod answers;
+mod cli;
 mod days;
+mod dot;
 mod input;
 mod math;
+mod parse;
+mod pathfinding;
 mod geometry;
+mod runner;
 
-use std::str::FromStr;
-use days::day20::{Input, *};
-use input::read_from_file;
+use std::{fs, path::{Path, PathBuf}, process::ExitCode};
+use answers::{Answers, Outcome};
+use cli::{AllArgs, Command, DotArgs, InputArgs, RunArgs, VerifyArgs};
+use input::{Inputs, LocalFetcher, Provider};
+use runner::Part;
 
-fn main() {
-    let input = Input::from_str(&read_from_file(20)).unwrap();
-    use std::time::Instant;
-    let now = Instant::now();
-    {
-        println!("{}", part2(input));
+fn main() -> ExitCode {
+    match cli::parse_args(std::env::args().skip(1)) {
+        Ok(Command::Help) => {
+            println!("{}", cli::USAGE);
+            ExitCode::SUCCESS
+        }
+        Ok(Command::Run(args)) => run(args),
+        Ok(Command::All(args)) => run_all(args),
+        Ok(Command::Verify(args)) => verify(args),
+        Ok(Command::Dot(args)) => dot(args),
+        Err(e) => {
+            eprintln!("error: {e}\n\n{}", cli::USAGE);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run(args: RunArgs) -> ExitCode {
+    let Some(solution) = days::get(args.day) else {
+        eprintln!("error: day {} is not implemented", args.day);
+        return ExitCode::FAILURE;
+    };
+    let input = inputs(&args.inputs)
+        .and_then(|inputs| inputs.read(args.input.as_ref(), args.day).map_err(|e| e.to_string()));
+    let input = match input {
+        Ok(input) => input,
+        Err(e) => {
+            eprintln!("error: {e}");
+            return ExitCode::FAILURE;
+        }
+    };
+    let parts = match args.part {
+        Some(part) => vec![part],
+        None => Part::both().to_vec(),
+    };
+    let report = match solution.solve(&input, &parts) {
+        Ok(report) => report,
+        Err(e) => {
+            eprintln!("error: invalid input: {e}");
+            return ExitCode::FAILURE;
+        }
+    };
+    println!("Parse: {:.2?}", report.parse);
+    for answer in &report.answers {
+        println!("Day {} part {}: {} ({:.2?})", args.day, answer.part, answer.value, answer.elapsed);
+    }
+    ExitCode::SUCCESS
+}
+
+fn run_all(args: AllArgs) -> ExitCode {
+    let inputs = match inputs(&args.inputs) {
+        Ok(inputs) => inputs,
+        Err(e) => {
+            eprintln!("error: {e}");
+            return ExitCode::FAILURE;
+        }
+    };
+    let mut reports = Vec::new();
+    for solution in days::SOLUTIONS {
+        match inputs.read(None, solution.day) {
+            Ok(input) => match solution.solve(&input, &Part::both()) {
+                Ok(report) => reports.push(report),
+                Err(e) => eprintln!("skipping day {}: invalid input: {e}", solution.day),
+            },
+            Err(input::InputError::NotFound(path)) => {
+                eprintln!("skipping day {}: no input at {}", solution.day, path.display())
+            }
+            Err(e) => eprintln!("skipping day {}: {e}", solution.day),
+        }
+    }
+    println!("{}", runner::format_table(&reports));
+    ExitCode::SUCCESS
+}
+
+fn verify(args: VerifyArgs) -> ExitCode {
+    let path = args.answers.as_deref().unwrap_or(Path::new(answers::DEFAULT_PATH));
+    let answers = match Answers::load(path) {
+        Ok(answers) => answers,
+        Err(e) => {
+            eprintln!("error: {e}");
+            return ExitCode::FAILURE;
+        }
+    };
+    if let Some(day) = args.day.filter(|&day| days::get(day).is_none()) {
+        eprintln!("error: day {day} is not implemented");
+        return ExitCode::FAILURE;
+    }
+    let inputs = match inputs(&args.inputs) {
+        Ok(inputs) => inputs,
+        Err(e) => {
+            eprintln!("error: {e}");
+            return ExitCode::FAILURE;
+        }
+    };
+    let (mut passed, mut failed, mut missing) = (0, 0, 0);
+    for solution in days::SOLUTIONS.iter().filter(|s| args.day.is_none_or(|day| s.day == day)) {
+        let report = inputs
+            .read(None, solution.day)
+            .map_err(|e| e.to_string())
+            .and_then(|input| solution.solve(&input, &Part::both()).map_err(|e| e.to_string()));
+        let report = match report {
+            Ok(report) => report,
+            Err(e) => {
+                println!("day {:>2}: FAIL ({e})", solution.day);
+                failed += 2;
+                continue;
+            }
+        };
+        for (part, outcome) in answers.verify(&report) {
+            match outcome {
+                Outcome::Pass => passed += 1,
+                Outcome::Fail { .. } => failed += 1,
+                Outcome::Missing => missing += 1,
+            }
+            println!("day {:>2} part {part}: {outcome} got {}", solution.day, report.answer(part).unwrap_or("-"));
+        }
+    }
+    println!("{passed} passed, {failed} failed, {missing} missing");
+    if failed == 0 { ExitCode::SUCCESS } else { ExitCode::FAILURE }
+}
+
+fn dot(args: DotArgs) -> ExitCode {
+    let Some(solution) = days::get(args.day) else {
+        eprintln!("error: day {} is not implemented", args.day);
+        return ExitCode::FAILURE;
+    };
+    let input = inputs(&args.inputs)
+        .and_then(|inputs| inputs.read(args.input.as_ref(), args.day).map_err(|e| e.to_string()));
+    let input = match input {
+        Ok(input) => input,
+        Err(e) => {
+            eprintln!("error: {e}");
+            return ExitCode::FAILURE;
+        }
+    };
+    let graph = match solution.dot(&input) {
+        Some(Ok(graph)) => graph,
+        Some(Err(e)) => {
+            eprintln!("error: invalid input: {e}");
+            return ExitCode::FAILURE;
+        }
+        None => {
+            eprintln!("error: day {} has no graph to export", args.day);
+            return ExitCode::FAILURE;
+        }
+    };
+    let path = args.output.unwrap_or_else(|| PathBuf::from(format!("day{}.dot", args.day)));
+    if let Err(e) = fs::write(&path, graph + "\n") {
+        eprintln!("error: cannot write {}: {e}", path.display());
+        return ExitCode::FAILURE;
+    }
+    println!("Wrote {}", path.display());
+    ExitCode::SUCCESS
+}
+
+fn inputs(args: &InputArgs) -> Result<Inputs, String> {
+    let inputs = Inputs::new(input::root(args.input_dir.as_deref()));
+    match (input::cache_root(args.cache_dir.as_deref()), &args.fetch_dir) {
+        (Some(cache), fetch_dir) => {
+            let provider = Provider::new(cache);
+            let provider = match fetch_dir {
+                Some(dir) => provider.with_fetcher(LocalFetcher::new(dir)),
+                None => provider,
+            };
+            Ok(inputs.with_provider(provider))
+        }
+        (None, Some(_)) => Err(format!("--fetch-dir needs --cache-dir or {}", input::CACHE_VAR)),
+        (None, None) => Ok(inputs),
     }
-    let elapsed = now.elapsed();
-    println!("Elapsed: {:.2?}", elapsed);
 }
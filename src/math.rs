@@ -1,29 +1,21 @@
-pub fn gcd(a: i64, b: i64) -> i64 {
-    if a == 0 || b == 0 {
-        return a ^ b;
-    }
-
-    let mut a = a.abs();
-    let mut b = b.abs();
+use std::ops::{Neg, Rem};
 
-    let shift = (a | b).trailing_zeros();
-    a >>= a.trailing_zeros();
-
-    while b != 0 {
-        b >>= b.trailing_zeros();
-        if a > b {
-            std::mem::swap(&mut a, &mut b);
-        }
-        b -= a;
+/// Greatest common divisor of any signed integers, never negative.
+pub fn gcd<T>(a: T, b: T) -> T
+where
+    T: Copy + Default + PartialOrd + Rem<Output = T> + Neg<Output = T>,
+{
+    let (mut a, mut b) = (a, b);
+    while b != T::default() {
+        (a, b) = (b, a % b);
     }
-
-    a << shift
+    if a < T::default() { -a } else { a }
 }
 
 pub fn gcd_vec(numbers: Vec<i64>) -> i64 {
     numbers
         .into_iter()
-        .reduce(|a, b| gcd(a, b))
+        .reduce(gcd)
         .expect("gcd calculation panicked")
 }
 
@@ -34,7 +26,7 @@ pub fn lcm(a: i64, b: i64) -> i64 {
 pub fn lcm_vec(numbers: Vec<i64>) -> i64 {
     numbers
         .into_iter()
-        .reduce(|a, b| lcm(a, b))
+        .reduce(lcm)
         .expect("lcm calculation panicked")
 }
 
@@ -49,6 +41,8 @@ mod tests {
         assert_eq!(13, gcd(13, 39));
         assert_eq!(69, gcd(0, 69));
         assert_eq!(420, gcd(420, 0));
+        assert_eq!(6, gcd(-12, 18));
+        assert_eq!(3, gcd(9_i128 << 100, -3));
     }
 
     #[test]
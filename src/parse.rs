@@ -0,0 +1,203 @@
+use std::{fmt, str::FromStr};
+
+/// Error returned by every input parser, pointing at the offending token.
+///
+/// `line` and `column` are 1-based and relative to the text the error was
+/// created from; `within` shifts them when that text is part of a larger one.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct ParseError {
+    pub day: Option<u32>,
+    pub line: usize,
+    pub column: usize,
+    pub expected: String,
+    pub found: String,
+}
+
+impl ParseError {
+    /// Creates an error for `token`, which should be a slice of `s`.
+    pub fn new(s: &str, token: &str, expected: impl Into<String>) -> Self {
+        let error = ParseError {
+            day: None,
+            line: 1,
+            column: 1,
+            expected: expected.into(),
+            found: token.to_owned(),
+        };
+        error.within(s, token)
+    }
+
+    /// Relocates an error created from `inner` so it is relative to `outer`.
+    pub fn within(mut self, outer: &str, inner: &str) -> Self {
+        let Some(prefix) = offset(outer, inner).map(|offset| &outer[..offset]) else {
+            return self;
+        };
+        let last_line = prefix.rsplit('\n').next().unwrap_or_default();
+        if self.line == 1 {
+            self.column += last_line.chars().count();
+        }
+        self.line += prefix.matches('\n').count();
+        self
+    }
+
+    pub fn for_day(mut self, day: u32) -> Self {
+        self.day = Some(day);
+        self
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(day) = self.day {
+            write!(f, "day {day}, ")?;
+        }
+        write!(f, "line {}, column {}: expected {}, ", self.line, self.column, self.expected)?;
+        if self.found.is_empty() {
+            write!(f, "found nothing")
+        } else {
+            write!(f, "found `{}`", self.found)
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+fn offset(outer: &str, inner: &str) -> Option<usize> {
+    let start = outer.as_ptr() as usize;
+    let at = inner.as_ptr() as usize;
+    (start..=start + outer.len())
+        .contains(&at)
+        .then_some(at - start)
+}
+
+/// Parses `token` (a slice of `s`) as a number, ignoring surrounding whitespace.
+pub fn number<T: FromStr>(s: &str, token: &str) -> Result<T, ParseError> {
+    let token = token.trim();
+    token.parse().map_err(|_| ParseError::new(s, token, "a number"))
+}
+
+/// Parses exactly `N` numbers out of `tokens`, which should be slices of `s`.
+pub fn numbers<'a, T: FromStr, const N: usize>(
+    s: &str,
+    tokens: impl IntoIterator<Item = &'a str>,
+) -> Result<[T; N], ParseError> {
+    let mut tokens = tokens.into_iter();
+    let mut numbers = Vec::with_capacity(N);
+    let mut end = &s[s.len()..];
+    for _ in 0..N {
+        let token = tokens.next().unwrap_or(end);
+        end = &token[token.len()..];
+        numbers.push(number(s, token)?);
+    }
+    if let Some(extra) = tokens.next() {
+        return Err(ParseError::new(s, extra, format!("only {N} numbers")));
+    }
+    Ok(numbers.try_into().unwrap_or_else(|_| unreachable!()))
+}
+
+/// Splits `token` (a slice of `s`) around the first `delimiter`.
+pub fn split_once<'a>(s: &str, token: &'a str, delimiter: &str) -> Result<(&'a str, &'a str), ParseError> {
+    token
+        .split_once(delimiter)
+        .ok_or_else(|| ParseError::new(s, token, format!("`{delimiter}`")))
+}
+
+/// Parses every line of `s` with `f`, locating errors within `s`.
+pub fn lines<T, F>(s: &str, f: F) -> Result<Vec<T>, ParseError>
+where
+    F: Fn(&str) -> Result<T, ParseError>,
+{
+    s.lines()
+        .map(|line| f(line).map_err(|e| e.within(s, line)))
+        .collect()
+}
+
+/// Parses every `\n\n`-separated block of `s` with `f`, locating errors within `s`.
+pub fn blocks<T, F>(s: &str, f: F) -> Result<Vec<T>, ParseError>
+where
+    F: Fn(&str) -> Result<T, ParseError>,
+{
+    s.split("\n\n")
+        .map(|block| f(block).map_err(|e| e.within(s, block)))
+        .collect()
+}
+
+/// Parses the characters of a rectangular character map into rows with `f`,
+/// which returns `None` for characters it does not accept.
+pub fn map<T, F>(s: &str, expected: &str, f: F) -> Result<Vec<Vec<T>>, ParseError>
+where
+    F: Fn(char) -> Option<T>,
+{
+    lines(s, |line| {
+        line.char_indices()
+            .map(|(i, c)| f(c).ok_or_else(|| ParseError::new(line, &line[i..i + c.len_utf8()], expected)))
+            .collect()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn error_position() {
+        let s = "12 ab 3";
+        let e = ParseError::new(s, &s[3..5], "a number");
+        assert_eq!((1, 4), (e.line, e.column));
+        assert_eq!("line 1, column 4: expected a number, found `ab`", e.to_string());
+    }
+
+    #[test]
+    fn error_within() {
+        let s = "1 2\n3 x\n5 6";
+        let line = s.lines().nth(1).unwrap();
+        let e = ParseError::new(line, &line[2..], "a number").within(s, line);
+        assert_eq!((2, 3), (e.line, e.column));
+        assert_eq!("day 9, line 2, column 3: expected a number, found `x`", e.for_day(9).to_string());
+    }
+
+    #[test]
+    fn test_number() {
+        assert_eq!(Ok(42), number::<i32>("x 42", " 42 "));
+        let s = "x=?";
+        let e = number::<i32>(s, &s[2..]).unwrap_err();
+        assert_eq!(3, e.column);
+    }
+
+    #[test]
+    fn test_numbers() {
+        let s = "1, 2, 3";
+        assert_eq!(Ok([1, 2, 3]), numbers::<i32, 3>(s, s.split(',')));
+        let e = numbers::<i32, 3>(s, s.split(',').take(2)).unwrap_err();
+        assert_eq!((5, ""), (e.column, e.found.as_str()));
+        let e = numbers::<i32, 2>(s, s.split(',')).unwrap_err();
+        assert_eq!(("only 2 numbers", 6), (e.expected.as_str(), e.column));
+    }
+
+    #[test]
+    fn test_split_once() {
+        assert_eq!(Ok(("a", "b")), split_once("a: b", "a: b", ": "));
+        assert_eq!("`: `", split_once("ab", "ab", ": ").unwrap_err().expected);
+    }
+
+    #[test]
+    fn test_lines() {
+        let parsed = lines("1\n2\n3", |line| number::<i32>(line, line));
+        assert_eq!(Ok(vec![1, 2, 3]), parsed);
+        let e = lines("1\n2\n3x", |line| number::<i32>(line, line)).unwrap_err();
+        assert_eq!((3, 1), (e.line, e.column));
+    }
+
+    #[test]
+    fn test_blocks() {
+        let e = blocks("1\n2\n\n3\n4x", |block| lines(block, |line| number::<i32>(line, line))).unwrap_err();
+        assert_eq!((5, 1), (e.line, e.column));
+    }
+
+    #[test]
+    fn test_map() {
+        let digit = |c: char| c.to_digit(10);
+        assert_eq!(Ok(vec![vec![1, 2], vec![3, 4]]), map("12\n34", "a digit", digit));
+        let e = map("12\n3x", "a digit", digit).unwrap_err();
+        assert_eq!((2, 2, "x"), (e.line, e.column, e.found.as_str()));
+    }
+}
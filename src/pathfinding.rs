@@ -0,0 +1,199 @@
+use std::{
+    cmp::Reverse,
+    collections::{hash_map::Entry, BinaryHeap, HashMap, VecDeque},
+    hash::Hash,
+    ops::Add,
+};
+
+/// Cheapest route found by a search: its total cost and every state visited
+/// along the way, from the start to the goal inclusive.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Path<S, C> {
+    pub cost: C,
+    pub states: Vec<S>,
+}
+
+/// Breadth-first search where every move costs one step.
+pub fn bfs<S, I>(
+    start: S,
+    mut successors: impl FnMut(&S) -> I,
+    mut success: impl FnMut(&S) -> bool,
+) -> Option<Path<S, usize>>
+where
+    S: Clone + Eq + Hash,
+    I: IntoIterator<Item = S>,
+{
+    let mut visited = Visited::new(start, 0);
+    let mut queue = VecDeque::from([0]);
+    while let Some(index) = queue.pop_front() {
+        let (state, steps) = (visited.states[index].clone(), visited.costs[index]);
+        if success(&state) {
+            return Some(visited.path(index));
+        }
+        for next in successors(&state) {
+            if let Some(next) = visited.improve(next, steps + 1, index) {
+                queue.push_back(next);
+            }
+        }
+    }
+    None
+}
+
+/// Dijkstra's algorithm over non-negative edge costs.
+pub fn dijkstra<S, C, I>(
+    start: S,
+    successors: impl FnMut(&S) -> I,
+    success: impl FnMut(&S) -> bool,
+) -> Option<Path<S, C>>
+where
+    S: Clone + Eq + Hash,
+    C: Copy + Ord + Default + Add<Output = C>,
+    I: IntoIterator<Item = (S, C)>,
+{
+    astar(start, successors, |_| C::default(), success)
+}
+
+/// A* search; `heuristic` must never overestimate the remaining cost to a
+/// goal, or the path returned may not be the cheapest.
+pub fn astar<S, C, I>(
+    start: S,
+    mut successors: impl FnMut(&S) -> I,
+    mut heuristic: impl FnMut(&S) -> C,
+    mut success: impl FnMut(&S) -> bool,
+) -> Option<Path<S, C>>
+where
+    S: Clone + Eq + Hash,
+    C: Copy + Ord + Default + Add<Output = C>,
+    I: IntoIterator<Item = (S, C)>,
+{
+    let mut heap = BinaryHeap::from([Reverse((heuristic(&start), C::default(), 0))]);
+    let mut visited = Visited::new(start, C::default());
+    while let Some(Reverse((_, cost, index))) = heap.pop() {
+        if cost > visited.costs[index] {
+            continue;
+        }
+        let state = visited.states[index].clone();
+        if success(&state) {
+            return Some(visited.path(index));
+        }
+        for (next, step) in successors(&state) {
+            let estimate = heuristic(&next);
+            if let Some(next) = visited.improve(next, cost + step, index) {
+                heap.push(Reverse((visited.costs[next] + estimate, visited.costs[next], next)));
+            }
+        }
+    }
+    None
+}
+
+/// Every state seen so far, indexed in discovery order, with the cheapest
+/// known cost to reach it and the state it was reached from.
+struct Visited<S, C> {
+    states: Vec<S>,
+    costs: Vec<C>,
+    parents: Vec<usize>,
+    indices: HashMap<S, usize>,
+}
+
+impl<S: Clone + Eq + Hash, C: Copy + Ord> Visited<S, C> {
+    fn new(start: S, cost: C) -> Self {
+        Visited {
+            states: vec![start.clone()],
+            costs: vec![cost],
+            parents: vec![usize::MAX],
+            indices: HashMap::from([(start, 0)]),
+        }
+    }
+
+    /// Records reaching `state` from `parent` at `cost`, returning its index
+    /// if that is cheaper than any route known before.
+    fn improve(&mut self, state: S, cost: C, parent: usize) -> Option<usize> {
+        match self.indices.entry(state) {
+            Entry::Occupied(entry) => {
+                let index = *entry.get();
+                if cost >= self.costs[index] {
+                    return None;
+                }
+                self.costs[index] = cost;
+                self.parents[index] = parent;
+                Some(index)
+            }
+            Entry::Vacant(entry) => {
+                self.states.push(entry.key().clone());
+                self.costs.push(cost);
+                self.parents.push(parent);
+                Some(*entry.insert(self.states.len() - 1))
+            }
+        }
+    }
+
+    fn path(&self, goal: usize) -> Path<S, C> {
+        let mut states = Vec::new();
+        let mut index = goal;
+        while index != usize::MAX {
+            states.push(self.states[index].clone());
+            index = self.parents[index];
+        }
+        states.reverse();
+        Path { cost: self.costs[goal], states }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::geometry::{Grid, Point};
+
+    const MAZE: &str = "\
+S.#.....
+.##.###.
+....#...
+.####.#.
+......#G";
+
+    fn maze() -> Grid<u8> {
+        Grid::parse(MAZE, "a tile", |c| Some(c as u8)).unwrap()
+    }
+
+    fn open_neighbors(maze: &Grid<u8>, pos: (i32, i32)) -> Vec<(i32, i32)> {
+        maze.neighbors(pos).filter(|&p| maze[p] != b'#').collect()
+    }
+
+    #[test]
+    fn test_bfs() {
+        let maze = maze();
+        let path = bfs((0, 0), |&p| open_neighbors(&maze, p), |&p| maze[p] == b'G').unwrap();
+        assert_eq!(15, path.cost);
+        assert_eq!(16, path.states.len());
+        assert_eq!(Some(&(0, 0)), path.states.first());
+        assert_eq!(Some(&(4, 7)), path.states.last());
+        assert!(path.states.windows(2).all(|w| Point::from(w[0]).manhattan_distance(Point::from(w[1])) == 1));
+
+        let start = bfs((0, 0), |&p| open_neighbors(&maze, p), |_| true).unwrap();
+        assert_eq!(Path { cost: 0, states: vec![(0, 0)] }, start);
+        assert_eq!(None, bfs((0, 0), |&p| open_neighbors(&maze, p), |&p| p == (0, 2)));
+    }
+
+    #[test]
+    fn test_dijkstra() {
+        // Adding one costs 1, adding two costs 3 and doubling costs 4.
+        let successors = |&n: &u32| [(n + 1, 1), (n + 2, 3), (n * 2, 4)];
+        let path = dijkstra(1, successors, |&n| n == 10).unwrap();
+        assert_eq!(8, path.cost);
+        assert_eq!(vec![1, 2, 3, 4, 5, 10], path.states);
+        let odd = |&n: &u32| (n < 20).then_some((n + 2, 1));
+        assert_eq!(None, dijkstra(1, odd, |&n| n % 2 == 0));
+    }
+
+    #[test]
+    fn test_astar() {
+        let maze = maze();
+        let goal = Point(4, 7);
+        let successors = |&p: &(i32, i32)| open_neighbors(&maze, p).into_iter().map(|p| (p, 1));
+        let heuristic = |&p: &(i32, i32)| Point::from(p).manhattan_distance(goal);
+        let path = astar((0, 0), successors, heuristic, |&p| Point::from(p) == goal).unwrap();
+        let expected = bfs((0, 0), |&p| open_neighbors(&maze, p), |&p| Point::from(p) == goal).unwrap();
+        assert_eq!(expected.cost as i32, path.cost);
+        assert_eq!(expected.states.len(), path.states.len());
+    }
+}
@@ -0,0 +1,120 @@
+use std::time::Duration;
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Part {
+    One,
+    Two,
+}
+
+impl Part {
+    pub fn both() -> [Part; 2] {
+        [Part::One, Part::Two]
+    }
+}
+
+impl std::fmt::Display for Part {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Part::One => write!(f, "1"),
+            Part::Two => write!(f, "2"),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Answer {
+    pub part: Part,
+    pub value: String,
+    pub elapsed: Duration,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Report {
+    pub day: u32,
+    pub parse: Duration,
+    pub answers: Vec<Answer>,
+}
+
+impl Report {
+    pub fn answer(&self, part: Part) -> Option<&str> {
+        self.answers
+            .iter()
+            .find(|a| a.part == part)
+            .map(|a| a.value.as_str())
+    }
+
+    pub fn elapsed(&self, part: Part) -> Option<Duration> {
+        self.answers.iter().find(|a| a.part == part).map(|a| a.elapsed)
+    }
+
+    pub fn total(&self) -> Duration {
+        self.parse + self.answers.iter().map(|a| a.elapsed).sum::<Duration>()
+    }
+}
+
+/// Formats one row per report with both answers and the parse/part timings,
+/// followed by the overall total.
+pub fn format_table(reports: &[Report]) -> String {
+    const ANSWER_WIDTH: usize = 16;
+    const TIME_WIDTH: usize = 10;
+    let time = |d: Option<Duration>| d.map_or(String::from("-"), |d| format!("{d:.2?}"));
+
+    let mut table = format!(
+        "{:>3} | {:<ANSWER_WIDTH$} | {:<ANSWER_WIDTH$} | {:>TIME_WIDTH$} | {:>TIME_WIDTH$} | {:>TIME_WIDTH$} | {:>TIME_WIDTH$}\n",
+        "Day", "Part 1", "Part 2", "Parse", "Part 1", "Part 2", "Total"
+    );
+    table.push_str(&format!(
+        "{:-<4}+{:-<w$}+{:-<w$}+{:-<t$}+{:-<t$}+{:-<t$}+{:-<t$}\n",
+        "", "", "", "", "", "", "",
+        w = ANSWER_WIDTH + 2,
+        t = TIME_WIDTH + 2,
+    ));
+    for report in reports {
+        table.push_str(&format!(
+            "{:>3} | {:<ANSWER_WIDTH$} | {:<ANSWER_WIDTH$} | {:>TIME_WIDTH$} | {:>TIME_WIDTH$} | {:>TIME_WIDTH$} | {:>TIME_WIDTH$}\n",
+            report.day,
+            report.answer(Part::One).unwrap_or("-"),
+            report.answer(Part::Two).unwrap_or("-"),
+            time(Some(report.parse)),
+            time(report.elapsed(Part::One)),
+            time(report.elapsed(Part::Two)),
+            time(Some(report.total())),
+        ));
+    }
+    let total = reports.iter().map(Report::total).sum::<Duration>();
+    table.push_str(&format!("Total: {}", time(Some(total))));
+    table
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn report(day: u32, parse: u64, part1: u64, part2: u64) -> Report {
+        Report {
+            day,
+            parse: Duration::from_millis(parse),
+            answers: vec![
+                Answer { part: Part::One, value: format!("{}", day * 10), elapsed: Duration::from_millis(part1) },
+                Answer { part: Part::Two, value: format!("{}", day * 20), elapsed: Duration::from_millis(part2) },
+            ],
+        }
+    }
+
+    #[test]
+    fn report_total() {
+        assert_eq!(Duration::from_millis(6), report(1, 1, 2, 3).total());
+    }
+
+    #[test]
+    fn test_format_table() {
+        let table = format_table(&[report(1, 1, 2, 3), report(2, 0, 1, 1)]);
+        let lines = table.lines().collect::<Vec<_>>();
+        assert_eq!(5, lines.len());
+        assert!(lines[0].starts_with("Day | Part 1"));
+        assert!(lines[2].starts_with("  1 | 10 "));
+        assert!(lines[2].ends_with("6.00ms"));
+        assert!(lines[3].starts_with("  2 | 20 "));
+        assert_eq!("Total: 8.00ms", lines[4]);
+    }
+}